@@ -0,0 +1,125 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{Attachment, AttachmentKind};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+/// 附件文件保存在应用数据目录下的子目录，按提醒分开存放
+const ATTACHMENTS_DIR: &str = "attachments";
+
+pub struct AttachmentStore {
+    app_handle: AppHandle,
+}
+
+impl AttachmentStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    fn reminder_dir(&self, reminder_id: &str) -> AppResult<PathBuf> {
+        let data_dir = self
+            .app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Persistence(format!("Failed to resolve data dir: {}", e)))?;
+        Ok(data_dir.join(ATTACHMENTS_DIR).join(reminder_id))
+    }
+
+    /// 把文件复制到附件目录，返回复制后的路径
+    pub fn import_file(
+        &self,
+        reminder_id: &str,
+        attachment_id: &str,
+        source: &Path,
+    ) -> AppResult<PathBuf> {
+        if !source.is_file() {
+            return Err(AppError::Validation(format!(
+                "Attachment file not found: {}",
+                source.display()
+            )));
+        }
+        let file_name = source.file_name().ok_or_else(|| {
+            AppError::Validation(format!("Invalid attachment path: {}", source.display()))
+        })?;
+        self.copy_into(reminder_id, attachment_id, source, &file_name.to_string_lossy())
+    }
+
+    /// 为复制出的提醒复制附件文件，文件名去掉原附件 ID 前缀
+    pub fn duplicate_file(
+        &self,
+        reminder_id: &str,
+        attachment_id: &str,
+        attachment: &Attachment,
+    ) -> AppResult<PathBuf> {
+        let source = Path::new(&attachment.location);
+        let file_name = source
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .ok_or_else(|| {
+                AppError::Validation(format!("Invalid attachment path: {}", attachment.location))
+            })?;
+        let prefix = format!("{}-", attachment.id);
+        let file_name = file_name.strip_prefix(&prefix).unwrap_or(&file_name);
+        self.copy_into(reminder_id, attachment_id, source, file_name)
+    }
+
+    fn copy_into(
+        &self,
+        reminder_id: &str,
+        attachment_id: &str,
+        source: &Path,
+        file_name: &str,
+    ) -> AppResult<PathBuf> {
+        let dir = self.reminder_dir(reminder_id)?;
+        fs::create_dir_all(&dir).map_err(|e| {
+            AppError::Persistence(format!("Failed to create attachment dir: {}", e))
+        })?;
+
+        // 以附件 ID 为前缀，避免同名文件互相覆盖
+        let target = dir.join(format!("{}-{}", attachment_id, file_name));
+        fs::copy(source, &target)
+            .map_err(|e| AppError::Persistence(format!("Failed to copy attachment: {}", e)))?;
+        Ok(target)
+    }
+
+    /// 删除附件文件，文件已不存在时忽略
+    pub fn remove_file(&self, attachment: &Attachment) {
+        if attachment.kind != AttachmentKind::File {
+            return;
+        }
+        if let Err(e) = fs::remove_file(&attachment.location) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Failed to remove attachment {}: {}", attachment.location, e);
+            }
+        }
+    }
+
+    /// 删除提醒的全部附件文件
+    pub fn remove_reminder_files(&self, reminder_id: &str) {
+        let dir = match self.reminder_dir(reminder_id) {
+            Ok(dir) => dir,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+        if dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                eprintln!("Failed to remove attachments of {}: {}", reminder_id, e);
+            }
+        }
+    }
+
+    /// 用系统默认程序打开链接或文件
+    pub fn open(&self, attachment: &Attachment) -> AppResult<()> {
+        let opener = self.app_handle.opener();
+        let result = match attachment.kind {
+            AttachmentKind::Url => opener.open_url(&attachment.location, None::<&str>),
+            AttachmentKind::File => opener.open_path(&attachment.location, None::<&str>),
+        };
+        result.map_err(|e| {
+            AppError::Internal(format!("Failed to open {}: {}", attachment.location, e))
+        })
+    }
+}
@@ -1,7 +1,17 @@
-use crate::models::{CreateGroupRequest, CreateReminderRequest, Reminder, ReminderGroup, UpdateReminderRequest};
+use crate::cron_expr::{parse_schedule, CronExpr};
+use crate::error::CommandError;
+use crate::models::{
+    AppSettings, BulkResult, Context, CreateGroupRequest, CreateReminderRequest,
+    CreateRoutineRequest, DuplicatedGroup, HistoryRetention, OccurrenceList, OccurrenceLogEntry,
+    PauseReminderRequest, Priority, QuietHours, ReconcileReport, Reminder, ReminderGroup,
+    ReminderQuery, Routine, RoutineStepRequest, SavedReminder, ScheduleInput, SchedulerDiagnostics,
+    StatsReport, Tag, TagAction, TimeWindowInput, UpdateReminderRequest, UpdateRoutineRequest,
+    VacationSummary,
+};
+use crate::refresh_tray_menu;
 use crate::service::ReminderService;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 pub struct AppState {
     pub service: Arc<ReminderService>,
@@ -16,9 +26,14 @@ pub async fn get_groups(state: State<'_, AppState>) -> Result<Vec<ReminderGroup>
 pub async fn create_group(
     name: String,
     color: String,
+    contexts: Option<Vec<String>>,
     state: State<'_, AppState>,
-) -> Result<ReminderGroup, String> {
-    let request = CreateGroupRequest { name, color };
+) -> Result<ReminderGroup, CommandError> {
+    let request = CreateGroupRequest {
+        name,
+        color,
+        contexts,
+    };
     state.service.create_group(request).await.map_err(|e| e.into())
 }
 
@@ -37,20 +52,28 @@ pub async fn create_reminder(
     title: String,
     color: String,
     group_id: String,
-    cron_expression: Option<String>,
+    schedule: ScheduleInput,
     description: Option<String>,
-    start_at: Option<String>,
-    end_at: Option<String>,
+    checklist: Option<Vec<String>>,
+    windows: Option<Vec<TimeWindowInput>>,
+    max_per_day: Option<u32>,
+    contexts: Option<Vec<String>>,
+    priority: Option<Priority>,
+    tags: Option<Vec<String>>,
     state: State<'_, AppState>,
-) -> Result<Reminder, String> {
+) -> Result<SavedReminder, CommandError> {
     let request = CreateReminderRequest {
         title,
         color,
         group_id,
-        cron_expression,
+        schedule,
         description,
-        start_at,
-        end_at,
+        checklist,
+        windows,
+        max_per_day,
+        contexts,
+        priority,
+        tags,
     };
     state.service.create_reminder(request).await.map_err(|e| e.into())
 }
@@ -60,29 +83,71 @@ pub async fn update_reminder(
     reminder_id: String,
     title: Option<String>,
     color: Option<String>,
-    cron_expression: Option<String>,
+    schedule: Option<ScheduleInput>,
     description: Option<String>,
-    start_at: Option<String>,
-    end_at: Option<String>,
+    windows: Option<Vec<TimeWindowInput>>,
+    max_per_day: Option<u32>,
+    contexts: Option<Vec<String>>,
+    priority: Option<Priority>,
+    tags: Option<Vec<String>>,
     state: State<'_, AppState>,
-) -> Result<Reminder, String> {
+) -> Result<SavedReminder, CommandError> {
     let request = UpdateReminderRequest {
         title,
         color,
-        cron_expression,
+        schedule,
         description,
-        start_at,
-        end_at,
+        windows,
+        max_per_day,
+        contexts,
+        priority,
+        tags,
     };
     state.service.update_reminder(&reminder_id, request).await.map_err(|e| e.into())
 }
 
+/// 复制提醒，paused 为 true 时副本创建为暂停状态
+#[tauri::command]
+pub async fn duplicate_reminder(
+    reminder_id: String,
+    paused: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .duplicate_reminder(&reminder_id, paused.unwrap_or(false))
+        .await
+        .map_err(|e| e.into())
+}
+
+/// 复制分组及其提醒
+#[tauri::command]
+pub async fn duplicate_group(
+    group_id: String,
+    paused: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<DuplicatedGroup, String> {
+    state
+        .service
+        .duplicate_group(&group_id, paused.unwrap_or(false))
+        .await
+        .map_err(|e| e.into())
+}
+
 #[tauri::command]
 pub async fn pause_reminder(
     reminder_id: String,
+    until: Option<String>,
+    duration_minutes: Option<i64>,
+    notify_on_resume: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    state.service.pause_reminder(&reminder_id).await.map_err(|e| e.into())
+) -> Result<Reminder, String> {
+    let request = PauseReminderRequest {
+        until,
+        duration_minutes,
+        notify_on_resume,
+    };
+    state.service.pause_reminder(&reminder_id, request).await.map_err(|e| e.into())
 }
 
 #[tauri::command]
@@ -101,6 +166,134 @@ pub async fn cancel_reminder(
     state.service.cancel_reminder(&reminder_id).await.map_err(|e| e.into())
 }
 
+#[tauri::command]
+pub async fn complete_reminder(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.service.complete_reminder(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn acknowledge_reminder(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.acknowledge_reminder(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn add_checklist_item(
+    reminder_id: String,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.add_checklist_item(&reminder_id, &text).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn toggle_checklist_item(
+    reminder_id: String,
+    item_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .toggle_checklist_item(&reminder_id, &item_id)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn reorder_checklist(
+    reminder_id: String,
+    item_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .reorder_checklist(&reminder_id, &item_ids)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn remove_checklist_item(
+    reminder_id: String,
+    item_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .remove_checklist_item(&reminder_id, &item_id)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn add_url_attachment(
+    reminder_id: String,
+    url: String,
+    name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .add_url_attachment(&reminder_id, &url, name)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// 文件会被复制到应用数据目录，之后删除原文件不影响附件
+#[tauri::command]
+pub async fn add_file_attachment(
+    reminder_id: String,
+    path: String,
+    name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .add_file_attachment(&reminder_id, &path, name)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn remove_attachment(
+    reminder_id: String,
+    attachment_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .remove_attachment(&reminder_id, &attachment_id)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn open_attachment(
+    reminder_id: String,
+    attachment_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .service
+        .open_attachment(&reminder_id, &attachment_id)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// 按条件筛选并排序提醒，查询条件较多，直接接收整个查询对象
+#[tauri::command]
+pub async fn query_reminders(
+    query: ReminderQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<Reminder>, String> {
+    state.service.query_reminders(query).await.map_err(|e| e.into())
+}
+
 #[tauri::command]
 pub async fn delete_reminder(
     reminder_id: String,
@@ -108,3 +301,336 @@ pub async fn delete_reminder(
 ) -> Result<(), String> {
     state.service.delete_reminder(&reminder_id).await.map_err(|e| e.into())
 }
+
+#[tauri::command]
+pub async fn skip_next_occurrence(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.skip_next_occurrence(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn skip_occurrence_date(
+    reminder_id: String,
+    date: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.skip_occurrence_date(&reminder_id, &date).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn move_occurrence(
+    reminder_id: String,
+    occurrence_at: Option<i64>,
+    new_time: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state
+        .service
+        .move_occurrence(&reminder_id, occurrence_at, &new_time)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn clear_occurrence_overrides(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.clear_occurrence_overrides(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn get_occurrences(
+    from: i64,
+    to: i64,
+    state: State<'_, AppState>,
+) -> Result<OccurrenceList, String> {
+    state.service.get_occurrences(from, to).await.map_err(|e| e.into())
+}
+
+/// 查询触发记录，reminder_id 为空时返回全部提醒的记录
+#[tauri::command]
+pub async fn get_occurrence_log(
+    reminder_id: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<OccurrenceLogEntry>, String> {
+    state
+        .service
+        .get_occurrence_log(reminder_id.as_deref(), from, to)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// 把一次已送达的触发标记为完成或错过
+#[tauri::command]
+pub async fn mark_occurrence(
+    entry_id: String,
+    done: bool,
+    state: State<'_, AppState>,
+) -> Result<OccurrenceLogEntry, String> {
+    state.service.mark_occurrence(&entry_id, done).await.map_err(|e| e.into())
+}
+
+/// 完成率、连续完成次数等统计，供统计面板使用
+#[tauri::command]
+pub async fn get_stats(state: State<'_, AppState>) -> Result<StatsReport, String> {
+    state.service.get_stats().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn set_history_retention(
+    max_age_days: u32,
+    max_entries_per_reminder: u32,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    let retention = HistoryRetention {
+        max_age_days,
+        max_entries_per_reminder,
+    };
+    state.service.set_history_retention(retention).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn get_scheduler_diagnostics(
+    state: State<'_, AppState>,
+) -> Result<SchedulerDiagnostics, String> {
+    state.service.get_scheduler_diagnostics().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn reconcile_scheduler(state: State<'_, AppState>) -> Result<ReconcileReport, String> {
+    state.service.reconcile_scheduler().await.map_err(|e| e.into())
+}
+
+/// 把 cron 表达式（或英文描述）解析为结构化字段，供可视化编辑器使用
+#[tauri::command]
+pub fn parse_cron_expression(expression: String) -> Result<CronExpr, String> {
+    parse_schedule(&expression).map_err(|e| e.into())
+}
+
+/// 把编辑后的结构化字段校验后序列化回 cron 表达式
+#[tauri::command]
+pub fn format_cron_expression(expression: CronExpr) -> Result<String, String> {
+    expression.validate().map_err(String::from)?;
+    Ok(expression.to_string())
+}
+
+#[tauri::command]
+pub async fn get_routines(state: State<'_, AppState>) -> Result<Vec<Routine>, String> {
+    state.service.get_routines().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn create_routine(
+    name: String,
+    color: String,
+    steps: Vec<RoutineStepRequest>,
+    cron_expression: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Routine, String> {
+    let request = CreateRoutineRequest {
+        name,
+        color,
+        steps,
+        cron_expression,
+    };
+    state.service.create_routine(request).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn update_routine(
+    routine_id: String,
+    name: Option<String>,
+    color: Option<String>,
+    steps: Option<Vec<RoutineStepRequest>>,
+    cron_expression: Option<String>,
+    is_enabled: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Routine, String> {
+    let request = UpdateRoutineRequest {
+        name,
+        color,
+        steps,
+        cron_expression,
+        is_enabled,
+    };
+    state
+        .service
+        .update_routine(&routine_id, request)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn delete_routine(routine_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.service.delete_routine(&routine_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn start_routine(
+    routine_id: String,
+    state: State<'_, AppState>,
+) -> Result<Routine, String> {
+    state.service.start_routine(&routine_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn stop_routine(
+    routine_id: String,
+    state: State<'_, AppState>,
+) -> Result<Routine, String> {
+    state.service.stop_routine(&routine_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn acknowledge_routine_step(
+    routine_id: String,
+    state: State<'_, AppState>,
+) -> Result<Routine, String> {
+    state.service.acknowledge_routine_step(&routine_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn get_contexts(state: State<'_, AppState>) -> Result<Vec<Context>, String> {
+    state.service.get_contexts().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn create_context(
+    name: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Context, String> {
+    let context = state.service.create_context(name).await.map_err(String::from)?;
+    refresh_tray_menu(&app, &state.service).await;
+    Ok(context)
+}
+
+#[tauri::command]
+pub async fn delete_context(
+    context_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.service.delete_context(&context_id).await.map_err(String::from)?;
+    refresh_tray_menu(&app, &state.service).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_group_contexts(
+    group_id: String,
+    contexts: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<ReminderGroup, String> {
+    state
+        .service
+        .set_group_contexts(&group_id, contexts)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    state.service.get_settings().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn set_active_context(
+    context_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    let settings = state
+        .service
+        .set_active_context(context_id)
+        .await
+        .map_err(String::from)?;
+    refresh_tray_menu(&app, &state.service).await;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn start_vacation(
+    until: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    state.service.start_vacation(until).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn end_vacation(
+    state: State<'_, AppState>,
+) -> Result<Option<VacationSummary>, String> {
+    state.service.end_vacation().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn reactivate_after_vacation(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<Reminder, String> {
+    state.service.reactivate_after_vacation(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn dismiss_after_vacation(
+    reminder_id: String,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    state.service.dismiss_after_vacation(&reminder_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn set_quiet_hours(
+    quiet_hours: Option<QuietHours>,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    state.service.set_quiet_hours(quiet_hours).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn get_tags(state: State<'_, AppState>) -> Result<Vec<Tag>, String> {
+    state.service.get_tags().await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn create_tag(name: String, state: State<'_, AppState>) -> Result<Tag, String> {
+    state.service.create_tag(&name).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    tag_id: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<Tag, String> {
+    state.service.rename_tag(&tag_id, &name).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn merge_tags(
+    source_id: String,
+    target_id: String,
+    state: State<'_, AppState>,
+) -> Result<Tag, String> {
+    state.service.merge_tags(&source_id, &target_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn delete_tag(tag_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.service.delete_tag(&tag_id).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn apply_tag_action(
+    tag_id: String,
+    action: TagAction,
+    state: State<'_, AppState>,
+) -> Result<BulkResult, String> {
+    state.service.apply_tag_action(&tag_id, action).await.map_err(|e| e.into())
+}
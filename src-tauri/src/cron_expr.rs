@@ -0,0 +1,838 @@
+use crate::error::{AppError, AppResult};
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+use english_to_cron::str_cron_syntax;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 步长的起点：`*/5`、`10/5` 或 `10-40/5`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StepBase {
+    Any,
+    From { start: u32 },
+    Range { start: u32, end: u32 },
+}
+
+/// cron 字段中以逗号分隔的一项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CronPart {
+    /// `*`
+    Any,
+    /// `?`，仅用于日期和星期字段
+    NoSpecific,
+    Value { value: u32 },
+    Range { start: u32, end: u32 },
+    Step { base: StepBase, step: u32 },
+    /// 日期字段 `L` 或 `L-3`：月末（向前偏移若干天）
+    LastDay { offset: u32 },
+    /// 日期字段 `LW`：当月最后一个工作日
+    LastWeekday,
+    /// 日期字段 `15W`：离 15 号最近的工作日（不跨月）
+    NearestWeekday { day: u32 },
+    /// 星期字段 `5L`：当月最后一个星期 x
+    LastOfWeekday { weekday: u32 },
+    /// 星期字段 `2#1`：当月第 n 个星期 x
+    NthWeekday { weekday: u32, nth: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Second,
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+    Year,
+}
+
+impl FieldKind {
+    fn name(self) -> &'static str {
+        match self {
+            FieldKind::Second => "second",
+            FieldKind::Minute => "minute",
+            FieldKind::Hour => "hour",
+            FieldKind::DayOfMonth => "day of month",
+            FieldKind::Month => "month",
+            FieldKind::DayOfWeek => "day of week",
+            FieldKind::Year => "year",
+        }
+    }
+}
+
+/// 解析后的 cron 表达式，支持 5 位（Unix）、6 位和 7 位（Quartz）格式。
+/// Unix 格式星期取值 0-7（0 和 7 为周日），Quartz 格式为 1-7（1 为周日）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronExpr {
+    /// 为空表示 5 位格式，秒固定为 0
+    pub seconds: Option<Vec<CronPart>>,
+    pub minutes: Vec<CronPart>,
+    pub hours: Vec<CronPart>,
+    pub days_of_month: Vec<CronPart>,
+    pub months: Vec<CronPart>,
+    pub days_of_week: Vec<CronPart>,
+    /// 仅 7 位格式有年份字段
+    pub years: Option<Vec<CronPart>>,
+}
+
+/// 查找下一次触发时最多向后搜索的天数
+const MAX_SEARCH_DAYS: i64 = 366 * 30;
+
+impl CronExpr {
+    pub fn parse(expression: &str) -> AppResult<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (seconds, rest, years) = match fields.len() {
+            5 => (None, &fields[..], None),
+            6 => (Some(fields[0]), &fields[1..], None),
+            7 => (Some(fields[0]), &fields[1..6], Some(fields[6])),
+            n => {
+                return Err(AppError::Validation(format!(
+                    "Cron expression must have 5, 6 or 7 fields, got {}",
+                    n
+                )))
+            }
+        };
+        let unix = seconds.is_none();
+
+        let expr = Self {
+            seconds: seconds
+                .map(|field| parse_field(field, FieldKind::Second, unix))
+                .transpose()?,
+            minutes: parse_field(rest[0], FieldKind::Minute, unix)?,
+            hours: parse_field(rest[1], FieldKind::Hour, unix)?,
+            days_of_month: parse_field(rest[2], FieldKind::DayOfMonth, unix)?,
+            months: parse_field(rest[3], FieldKind::Month, unix)?,
+            days_of_week: parse_field(rest[4], FieldKind::DayOfWeek, unix)?,
+            years: years
+                .map(|field| parse_field(field, FieldKind::Year, unix))
+                .transpose()?,
+        };
+        expr.validate()?;
+        Ok(expr)
+    }
+
+    /// 只在指定时刻触发一次的表达式
+    pub fn once_at(time: DateTime<Utc>) -> Self {
+        let value = |value: u32| vec![CronPart::Value { value }];
+        Self {
+            seconds: Some(value(time.second())),
+            minutes: value(time.minute()),
+            hours: value(time.hour()),
+            days_of_month: value(time.day()),
+            months: value(time.month()),
+            days_of_week: vec![CronPart::NoSpecific],
+            years: Some(value(time.year().max(0) as u32)),
+        }
+    }
+
+    /// 对结构化字段做与解析时相同的校验，供前端编辑后回传使用
+    pub fn validate(&self) -> AppResult<()> {
+        let unix = self.is_unix();
+        if let Some(seconds) = &self.seconds {
+            validate_parts(seconds, FieldKind::Second, unix)?;
+        }
+        validate_parts(&self.minutes, FieldKind::Minute, unix)?;
+        validate_parts(&self.hours, FieldKind::Hour, unix)?;
+        validate_parts(&self.days_of_month, FieldKind::DayOfMonth, unix)?;
+        validate_parts(&self.months, FieldKind::Month, unix)?;
+        validate_parts(&self.days_of_week, FieldKind::DayOfWeek, unix)?;
+        if let Some(years) = &self.years {
+            if unix {
+                return Err(AppError::Validation(
+                    "Year field requires a seconds field".to_string(),
+                ));
+            }
+            validate_parts(years, FieldKind::Year, unix)?;
+        }
+        Ok(())
+    }
+
+    fn is_unix(&self) -> bool {
+        self.seconds.is_none()
+    }
+
+    /// 只包含时分秒的表达式（日期字段为 `*`），交给 `job_scheduler_ng` 按时间触发
+    pub fn time_of_day_expression(&self) -> String {
+        let seconds = self
+            .seconds
+            .as_ref()
+            .map(|parts| format_parts(parts))
+            .unwrap_or_else(|| "0".to_string());
+        format!(
+            "{} {} {} * * *",
+            seconds,
+            format_parts(&self.minutes),
+            format_parts(&self.hours)
+        )
+    }
+
+    /// 判断某一天是否满足日期、月份、星期和年份字段
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.iter().any(|part| matches_simple(part, date.month(), 1, 12)) {
+            return false;
+        }
+        if let Some(years) = &self.years {
+            let year = date.year().max(0) as u32;
+            if !years.iter().any(|part| matches_simple(part, year, 1970, 2099)) {
+                return false;
+            }
+        }
+
+        let dom_restricted = is_restricted(&self.days_of_month);
+        let dow_restricted = is_restricted(&self.days_of_week);
+        let dom_matches = || {
+            self.days_of_month
+                .iter()
+                .any(|part| matches_day_of_month(part, date))
+        };
+        let dow_matches = || {
+            self.days_of_week
+                .iter()
+                .any(|part| matches_day_of_week(part, date, self.is_unix()))
+        };
+
+        match (dom_restricted, dow_restricted) {
+            (false, false) => true,
+            (true, false) => dom_matches(),
+            (false, true) => dow_matches(),
+            // Unix cron 同时指定日期和星期时满足其一即可（与 Vixie cron 相同，
+            // 以 `*` 开头的 `*/n` 不算指定），Quartz 要求同时满足
+            (true, true)
+                if self.is_unix()
+                    && !starts_with_any(&self.days_of_month)
+                    && !starts_with_any(&self.days_of_week) =>
+            {
+                dom_matches() || dow_matches()
+            }
+            (true, true) => dom_matches() && dow_matches(),
+        }
+    }
+
+    pub fn matches_time(&self, time: NaiveTime) -> bool {
+        let second_matches = match &self.seconds {
+            Some(seconds) => seconds
+                .iter()
+                .any(|part| matches_simple(part, time.second(), 0, 59)),
+            None => time.second() == 0,
+        };
+        second_matches
+            && self
+                .minutes
+                .iter()
+                .any(|part| matches_simple(part, time.minute(), 0, 59))
+            && self
+                .hours
+                .iter()
+                .any(|part| matches_simple(part, time.hour(), 0, 23))
+    }
+
+    /// 时分秒按 UTC 判断（与任务触发一致），日期字段按该时刻在 tz 中的日期判断，
+    /// 与时间窗口、按日期跳过和每日计数使用同一个日历日
+    pub fn matches<Tz: TimeZone>(&self, time: DateTime<Utc>, tz: &Tz) -> bool {
+        self.matches_date(time.with_timezone(tz).date_naive()) && self.matches_time(time.time())
+    }
+
+    /// 严格晚于 after 的下一次触发时间，日期字段按 tz 中的日期判断
+    pub fn next_after<Tz: TimeZone>(&self, after: DateTime<Utc>, tz: &Tz) -> Option<DateTime<Utc>> {
+        let seconds = match &self.seconds {
+            Some(parts) => expand(parts, 0, 59),
+            None => vec![0],
+        };
+        let minutes = expand(&self.minutes, 0, 59);
+        let hours = expand(&self.hours, 0, 23);
+
+        let local_date = |naive: NaiveDateTime| {
+            Utc.from_utc_datetime(&naive).with_timezone(tz).date_naive()
+        };
+        let start_date = after.date_naive();
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start_date + Duration::days(offset);
+            // 一个 UTC 日在 tz 中跨越开头和结尾两个日期，都不满足时跳过
+            let first = local_date(date.and_time(NaiveTime::MIN));
+            let last = local_date(date.and_hms_opt(23, 59, 59)?);
+            let (first_matches, last_matches) = (self.matches_date(first), self.matches_date(last));
+            if !first_matches && !last_matches {
+                continue;
+            }
+            for &hour in &hours {
+                for &minute in &minutes {
+                    for &second in &seconds {
+                        let Some(naive) = date.and_hms_opt(hour, minute, second) else {
+                            continue;
+                        };
+                        let candidate = Utc.from_utc_datetime(&naive);
+                        if candidate <= after {
+                            continue;
+                        }
+                        let matches = match local_date(naive) {
+                            day if day == first => first_matches,
+                            day if day == last => last_matches,
+                            day => self.matches_date(day),
+                        };
+                        if matches {
+                            return Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// 从 after 之后依次产生触发时间
+    pub fn upcoming<'a, Tz: TimeZone>(
+        &'a self,
+        after: DateTime<Utc>,
+        tz: &'a Tz,
+    ) -> impl Iterator<Item = DateTime<Utc>> + 'a {
+        let mut cursor = after;
+        std::iter::from_fn(move || {
+            let next = self.next_after(cursor, tz)?;
+            cursor = next;
+            Some(next)
+        })
+    }
+}
+
+/// 把提醒的 cron 表达式解析为调度计划：cron 语法直接解析（支持 L、W、#），
+/// 英文描述先转换为 cron 语法
+pub fn parse_schedule(cron_expression: &str) -> AppResult<CronExpr> {
+    let looks_like_cron = cron_expression
+        .trim_start()
+        .starts_with(|c: char| c.is_ascii_digit() || c == '*' || c == '?');
+    if looks_like_cron {
+        return CronExpr::parse(cron_expression);
+    }
+
+    let cron_expr = str_cron_syntax(cron_expression)
+        .map_err(|e| AppError::Scheduler(format!("Invalid cron expression: {}", e)))?;
+    CronExpr::parse(&cron_expr)
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::with_capacity(7);
+        if let Some(seconds) = &self.seconds {
+            fields.push(format_parts(seconds));
+        }
+        fields.push(format_parts(&self.minutes));
+        fields.push(format_parts(&self.hours));
+        fields.push(format_parts(&self.days_of_month));
+        fields.push(format_parts(&self.months));
+        fields.push(format_parts(&self.days_of_week));
+        if let Some(years) = &self.years {
+            fields.push(format_parts(years));
+        }
+        write!(f, "{}", fields.join(" "))
+    }
+}
+
+fn bounds(kind: FieldKind, unix: bool) -> (u32, u32) {
+    match kind {
+        FieldKind::Second | FieldKind::Minute => (0, 59),
+        FieldKind::Hour => (0, 23),
+        FieldKind::DayOfMonth => (1, 31),
+        FieldKind::Month => (1, 12),
+        FieldKind::DayOfWeek if unix => (0, 7),
+        FieldKind::DayOfWeek => (1, 7),
+        FieldKind::Year => (1970, 2099),
+    }
+}
+
+fn parse_field(field: &str, kind: FieldKind, unix: bool) -> AppResult<Vec<CronPart>> {
+    field
+        .split(',')
+        .map(|item| parse_part(item, kind, unix))
+        .collect()
+}
+
+fn parse_part(item: &str, kind: FieldKind, unix: bool) -> AppResult<CronPart> {
+    let invalid = || {
+        AppError::Validation(format!(
+            "Invalid {} field value: {}",
+            kind.name(),
+            item
+        ))
+    };
+    let upper = item.to_ascii_uppercase();
+
+    match upper.as_str() {
+        "*" => return Ok(CronPart::Any),
+        "?" => return Ok(CronPart::NoSpecific),
+        _ => {}
+    }
+
+    if kind == FieldKind::DayOfMonth {
+        if upper == "L" {
+            return Ok(CronPart::LastDay { offset: 0 });
+        }
+        if upper == "LW" {
+            return Ok(CronPart::LastWeekday);
+        }
+        if let Some(offset) = upper.strip_prefix("L-") {
+            let offset = offset.parse().map_err(|_| invalid())?;
+            return Ok(CronPart::LastDay { offset });
+        }
+        if let Some(day) = upper.strip_suffix('W') {
+            let day = day.parse().map_err(|_| invalid())?;
+            return Ok(CronPart::NearestWeekday { day });
+        }
+    }
+
+    if kind == FieldKind::DayOfWeek {
+        if let Some((weekday, nth)) = upper.split_once('#') {
+            let weekday = parse_value(weekday, kind, unix).ok_or_else(invalid)?;
+            let nth = nth.parse().map_err(|_| invalid())?;
+            return Ok(CronPart::NthWeekday { weekday, nth });
+        }
+        if let Some(weekday) = upper.strip_suffix('L') {
+            let weekday = parse_value(weekday, kind, unix).ok_or_else(invalid)?;
+            return Ok(CronPart::LastOfWeekday { weekday });
+        }
+    }
+
+    if let Some((base, step)) = upper.split_once('/') {
+        let step = step.parse().map_err(|_| invalid())?;
+        let base = if base == "*" {
+            StepBase::Any
+        } else if let Some((start, end)) = base.split_once('-') {
+            StepBase::Range {
+                start: parse_value(start, kind, unix).ok_or_else(invalid)?,
+                end: parse_value(end, kind, unix).ok_or_else(invalid)?,
+            }
+        } else {
+            StepBase::From {
+                start: parse_value(base, kind, unix).ok_or_else(invalid)?,
+            }
+        };
+        return Ok(CronPart::Step { base, step });
+    }
+
+    if let Some((start, end)) = upper.split_once('-') {
+        return Ok(CronPart::Range {
+            start: parse_value(start, kind, unix).ok_or_else(invalid)?,
+            end: parse_value(end, kind, unix).ok_or_else(invalid)?,
+        });
+    }
+
+    let value = parse_value(&upper, kind, unix).ok_or_else(invalid)?;
+    Ok(CronPart::Value { value })
+}
+
+/// 解析数字或月份、星期的英文缩写
+fn parse_value(value: &str, kind: FieldKind, unix: bool) -> Option<u32> {
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+
+    const MONTHS: [&str; 12] = [
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ];
+    const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+    match kind {
+        FieldKind::Month => MONTHS
+            .iter()
+            .position(|name| *name == value)
+            .map(|index| index as u32 + 1),
+        FieldKind::DayOfWeek => WEEKDAYS
+            .iter()
+            .position(|name| *name == value)
+            .map(|index| if unix { index as u32 } else { index as u32 + 1 }),
+        _ => None,
+    }
+}
+
+fn validate_parts(parts: &[CronPart], kind: FieldKind, unix: bool) -> AppResult<()> {
+    if parts.is_empty() {
+        return Err(AppError::Validation(format!(
+            "The {} field must not be empty",
+            kind.name()
+        )));
+    }
+
+    let (min, max) = bounds(kind, unix);
+    let in_range = |value: u32| value >= min && value <= max;
+    let invalid = |part: &CronPart| {
+        AppError::Validation(format!(
+            "Invalid {} field value: {}",
+            kind.name(),
+            format_part(part)
+        ))
+    };
+
+    for part in parts {
+        let valid = match part {
+            CronPart::Any => true,
+            CronPart::NoSpecific => {
+                !unix && matches!(kind, FieldKind::DayOfMonth | FieldKind::DayOfWeek)
+            }
+            CronPart::Value { value } => in_range(*value),
+            CronPart::Range { start, end } => in_range(*start) && in_range(*end) && start <= end,
+            CronPart::Step { base, step } => {
+                *step > 0
+                    && match base {
+                        StepBase::Any => true,
+                        StepBase::From { start } => in_range(*start),
+                        StepBase::Range { start, end } => {
+                            in_range(*start) && in_range(*end) && start <= end
+                        }
+                    }
+            }
+            CronPart::LastDay { offset } => kind == FieldKind::DayOfMonth && *offset < 31,
+            CronPart::LastWeekday => kind == FieldKind::DayOfMonth,
+            CronPart::NearestWeekday { day } => {
+                kind == FieldKind::DayOfMonth && (1..=31).contains(day)
+            }
+            CronPart::LastOfWeekday { weekday } => {
+                kind == FieldKind::DayOfWeek && in_range(*weekday)
+            }
+            CronPart::NthWeekday { weekday, nth } => {
+                kind == FieldKind::DayOfWeek && in_range(*weekday) && (1..=5).contains(nth)
+            }
+        };
+        if !valid {
+            return Err(invalid(part));
+        }
+    }
+    Ok(())
+}
+
+fn format_parts(parts: &[CronPart]) -> String {
+    parts.iter().map(format_part).collect::<Vec<_>>().join(",")
+}
+
+fn format_part(part: &CronPart) -> String {
+    match part {
+        CronPart::Any => "*".to_string(),
+        CronPart::NoSpecific => "?".to_string(),
+        CronPart::Value { value } => value.to_string(),
+        CronPart::Range { start, end } => format!("{}-{}", start, end),
+        CronPart::Step { base, step } => match base {
+            StepBase::Any => format!("*/{}", step),
+            StepBase::From { start } => format!("{}/{}", start, step),
+            StepBase::Range { start, end } => format!("{}-{}/{}", start, end, step),
+        },
+        CronPart::LastDay { offset: 0 } => "L".to_string(),
+        CronPart::LastDay { offset } => format!("L-{}", offset),
+        CronPart::LastWeekday => "LW".to_string(),
+        CronPart::NearestWeekday { day } => format!("{}W", day),
+        CronPart::LastOfWeekday { weekday } => format!("{}L", weekday),
+        CronPart::NthWeekday { weekday, nth } => format!("{}#{}", weekday, nth),
+    }
+}
+
+/// 字段是否对取值有限制（`*`、`?` 视为不限制）
+fn is_restricted(parts: &[CronPart]) -> bool {
+    !parts
+        .iter()
+        .any(|part| matches!(part, CronPart::Any | CronPart::NoSpecific))
+}
+
+/// 字段是否以 `*` 开头（如 `*/2`）
+fn starts_with_any(parts: &[CronPart]) -> bool {
+    matches!(
+        parts.first(),
+        Some(CronPart::Step {
+            base: StepBase::Any,
+            ..
+        })
+    )
+}
+
+/// 匹配不含修饰符的普通取值
+fn matches_simple(part: &CronPart, value: u32, min: u32, max: u32) -> bool {
+    match part {
+        CronPart::Any | CronPart::NoSpecific => true,
+        CronPart::Value { value: expected } => value == *expected,
+        CronPart::Range { start, end } => value >= *start && value <= *end,
+        CronPart::Step { base, step } => {
+            let (start, end) = match base {
+                StepBase::Any => (min, max),
+                StepBase::From { start } => (*start, max),
+                StepBase::Range { start, end } => (*start, *end),
+            };
+            value >= start && value <= end && (value - start).is_multiple_of(*step)
+        }
+        _ => false,
+    }
+}
+
+fn expand(parts: &[CronPart], min: u32, max: u32) -> Vec<u32> {
+    (min..=max)
+        .filter(|value| parts.iter().any(|part| matches_simple(part, *value, min, max)))
+        .collect()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(31)
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    date.weekday().num_days_from_monday() < 5
+}
+
+/// 离指定日期最近的工作日，不跨出当月
+fn nearest_weekday(year: i32, month: u32, day: u32) -> Option<u32> {
+    let last_day = days_in_month(year, month);
+    if day > last_day {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    let nearest = match date.weekday().num_days_from_monday() {
+        // 周六：优先前一天的周五，月初则顺延到周一
+        5 if day > 1 => day - 1,
+        5 => day + 2,
+        // 周日：优先后一天的周一，月末则提前到周五
+        6 if day < last_day => day + 1,
+        6 => day - 2,
+        _ => day,
+    };
+    Some(nearest)
+}
+
+fn matches_day_of_month(part: &CronPart, date: NaiveDate) -> bool {
+    let last_day = days_in_month(date.year(), date.month());
+    match part {
+        CronPart::LastDay { offset } => last_day.checked_sub(*offset) == Some(date.day()),
+        CronPart::LastWeekday => {
+            let last_weekday = (1..=last_day)
+                .rev()
+                .find(|day| {
+                    NaiveDate::from_ymd_opt(date.year(), date.month(), *day)
+                        .is_some_and(is_weekday)
+                });
+            last_weekday == Some(date.day())
+        }
+        CronPart::NearestWeekday { day } => {
+            nearest_weekday(date.year(), date.month(), *day) == Some(date.day())
+        }
+        _ => matches_simple(part, date.day(), 1, 31),
+    }
+}
+
+fn matches_day_of_week(part: &CronPart, date: NaiveDate, unix: bool) -> bool {
+    // 统一换算为以周日为 0 的编号
+    let normalize = |weekday: u32| if unix { weekday % 7 } else { (weekday + 6) % 7 };
+    let today = date.weekday().num_days_from_sunday();
+    let last_day = days_in_month(date.year(), date.month());
+
+    match part {
+        CronPart::LastOfWeekday { weekday } => {
+            normalize(*weekday) == today && date.day() + 7 > last_day
+        }
+        CronPart::NthWeekday { weekday, nth } => {
+            normalize(*weekday) == today && (date.day() - 1) / 7 + 1 == *nth
+        }
+        CronPart::Value { value } => normalize(*value) == today,
+        CronPart::Range { start, end } => {
+            (*start..=*end).any(|weekday| normalize(weekday) == today)
+        }
+        CronPart::Step { .. } => {
+            let (min, max) = if unix { (0, 7) } else { (1, 7) };
+            (min..=max)
+                .filter(|weekday| matches_simple(part, *weekday, min, max))
+                .any(|weekday| normalize(weekday) == today)
+        }
+        _ => matches_simple(part, today, 0, 6),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&date(year, month, day).and_hms_opt(hour, minute, 0).unwrap())
+    }
+
+    /// 当月满足日期字段的所有日子
+    fn days_in(expression: &str, year: i32, month: u32) -> Vec<u32> {
+        let expr = CronExpr::parse(expression).unwrap();
+        (1..=days_in_month(year, month))
+            .filter(|day| expr.matches_date(date(year, month, *day)))
+            .collect()
+    }
+
+    #[test]
+    fn last_weekday() {
+        // 2024-08-31 是周六，2024-03-31 是周日，都回退到周五
+        assert_eq!(days_in("0 0 9 LW * ?", 2024, 8), vec![30]);
+        assert_eq!(days_in("0 0 9 LW * ?", 2024, 3), vec![29]);
+        // 2024-04-30 是周二
+        assert_eq!(days_in("0 0 9 LW * ?", 2024, 4), vec![30]);
+    }
+
+    #[test]
+    fn nearest_weekday_stays_in_month() {
+        // 2024-06-15 是周六，取周五；2024-09-15 是周日，取周一
+        assert_eq!(days_in("0 0 9 15W * ?", 2024, 6), vec![14]);
+        assert_eq!(days_in("0 0 9 15W * ?", 2024, 9), vec![16]);
+        // 2024-06-01 是周六，不能退到上个月，顺延到周一
+        assert_eq!(days_in("0 0 9 1W * ?", 2024, 6), vec![3]);
+        // 2024-03-31 是周日，不能进到下个月，提前到周五
+        assert_eq!(days_in("0 0 9 31W * ?", 2024, 3), vec![29]);
+        // 当月没有 31 号
+        assert!(days_in("0 0 9 31W * ?", 2024, 4).is_empty());
+    }
+
+    #[test]
+    fn last_day_with_offset() {
+        assert_eq!(days_in("0 0 9 L * ?", 2024, 2), vec![29]);
+        assert_eq!(days_in("0 0 9 L-3 * ?", 2024, 2), vec![26]);
+        assert_eq!(days_in("0 0 9 L-3 * ?", 2023, 2), vec![25]);
+    }
+
+    #[test]
+    fn nth_and_last_weekday_of_month() {
+        // Quartz 中 2 是周一，2024 年 1 月第一个周一是 1 号
+        assert_eq!(days_in("0 0 9 ? * 2#1", 2024, 1), vec![1]);
+        assert_eq!(days_in("0 0 9 ? * MON#3", 2024, 1), vec![15]);
+        // Quartz 中 5 是周四，Unix 中 5 是周五
+        assert_eq!(days_in("0 0 9 ? * 5L", 2024, 2), vec![29]);
+        assert_eq!(days_in("0 9 * * 5L", 2024, 2), vec![23]);
+        // 当月没有第五个周一
+        assert!(days_in("0 0 9 ? * 2#5", 2024, 2).is_empty());
+    }
+
+    #[test]
+    fn sunday_numbering() {
+        let sunday = date(2024, 1, 7);
+        let saturday = date(2024, 1, 6);
+
+        // Unix：0 和 7 都是周日
+        for expression in ["0 9 * * 0", "0 9 * * 7", "0 9 * * SUN"] {
+            let expr = CronExpr::parse(expression).unwrap();
+            assert!(expr.matches_date(sunday), "{}", expression);
+            assert!(!expr.matches_date(saturday), "{}", expression);
+        }
+        // Quartz：1 是周日，7 是周六
+        let expr = CronExpr::parse("0 0 9 ? * 1").unwrap();
+        assert!(expr.matches_date(sunday));
+        assert!(!expr.matches_date(saturday));
+        let expr = CronExpr::parse("0 0 9 ? * 7").unwrap();
+        assert!(expr.matches_date(saturday));
+        assert!(!expr.matches_date(sunday));
+        // Quartz 不接受 0
+        assert!(CronExpr::parse("0 0 9 ? * 0").is_err());
+    }
+
+    #[test]
+    fn invalid_modifiers_are_rejected() {
+        let invalid = [
+            "0 0 9 32W * ?",
+            "0 0 9 W * ?",
+            "0 0 9 L-31 * ?",
+            "0 0 9 ? * 2#6",
+            "0 0 9 ? * 2#0",
+            "0 0 9 ? * 8L",
+            "0 0 9 ? * L",
+            "0 0 9W * * ?",
+            "0 0 9 ? * 2W",
+            "0 0 9 2#1 * ?",
+        ];
+        for expression in invalid {
+            assert!(CronExpr::parse(expression).is_err(), "{}", expression);
+        }
+        for expression in ["0 0 9 LW * ?", "0 0 9 L-3 * ?", "0 0 9 ? * FRI#2", "0 0 9 ? * 6L"] {
+            assert!(CronExpr::parse(expression).is_ok(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn unix_day_of_month_or_day_of_week() {
+        // Unix：13 号或周五
+        let unix = days_in("0 9 13 * 5", 2024, 9);
+        assert_eq!(unix, vec![6, 13, 20, 27]);
+        let unix = days_in("0 9 13 * 5", 2024, 10);
+        assert_eq!(unix, vec![4, 11, 13, 18, 25]);
+        // Quartz：必须是 13 号且为周五
+        assert_eq!(days_in("0 0 9 13 * 6", 2024, 9), vec![13]);
+        assert!(days_in("0 0 9 13 * 6", 2024, 10).is_empty());
+    }
+
+    #[test]
+    fn star_step_does_not_trigger_or_rule() {
+        assert!(is_restricted(&CronExpr::parse("*/2 * * * *").unwrap().minutes));
+        assert!(!is_restricted(&CronExpr::parse("* * * * *").unwrap().minutes));
+        assert!(!is_restricted(&CronExpr::parse("0 0 0 ? * *").unwrap().days_of_month));
+
+        // 2024 年 4 月的周一为 1、8、15、22、29 号；`*/2` 只取单数日，两者同时满足
+        assert_eq!(days_in("0 0 */2 * 1", 2024, 4), vec![1, 15, 29]);
+        assert_eq!(days_in("0 0 1-31/14 * 1", 2024, 4), vec![1, 8, 15, 22, 29]);
+        // 只有日期字段时按步长匹配
+        assert_eq!(days_in("0 0 */10 * *", 2024, 4), vec![1, 11, 21]);
+    }
+
+    #[test]
+    fn next_after_crosses_month_and_year() {
+        let month_end = CronExpr::parse("0 12 L * *").unwrap();
+        assert_eq!(
+            month_end.next_after(at(2024, 1, 31, 13, 0), &Utc),
+            Some(at(2024, 2, 29, 12, 0))
+        );
+
+        let new_year = CronExpr::parse("30 8 1 1 *").unwrap();
+        assert_eq!(new_year.next_after(at(2024, 6, 1, 0, 0), &Utc), Some(at(2025, 1, 1, 8, 30)));
+        // 严格晚于 after
+        assert_eq!(new_year.next_after(at(2025, 1, 1, 8, 30), &Utc), Some(at(2026, 1, 1, 8, 30)));
+
+        let weekday = CronExpr::parse("0 0 9 ? * MON-FRI").unwrap();
+        let times: Vec<_> = weekday.upcoming(at(2024, 12, 27, 10, 0), &Utc).take(3).collect();
+        assert_eq!(
+            times,
+            vec![at(2024, 12, 30, 9, 0), at(2024, 12, 31, 9, 0), at(2025, 1, 1, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn year_field_limits_search() {
+        let expr = CronExpr::parse("0 0 9 1 1 ? 2024").unwrap();
+        assert_eq!(expr.next_after(at(2023, 6, 1, 0, 0), &Utc), Some(at(2024, 1, 1, 9, 0)));
+        assert_eq!(expr.next_after(at(2024, 1, 1, 9, 0), &Utc), None);
+    }
+
+    #[test]
+    fn day_fields_use_local_date_near_midnight() {
+        // UTC 20:00 在东八区是次日 04:00
+        let east = FixedOffset::east_opt(8 * 3600).unwrap();
+        let last_day = CronExpr::parse("0 0 20 L * ?").unwrap();
+        assert!(last_day.matches(at(2024, 4, 30, 20, 0), &Utc));
+        assert!(!last_day.matches(at(2024, 4, 30, 20, 0), &east));
+        assert!(last_day.matches(at(2024, 4, 29, 20, 0), &east));
+        assert_eq!(
+            last_day.next_after(at(2024, 4, 1, 0, 0), &east),
+            Some(at(2024, 4, 29, 20, 0))
+        );
+        assert_eq!(
+            last_day.next_after(at(2024, 4, 29, 20, 0), &east),
+            Some(at(2024, 5, 30, 20, 0))
+        );
+
+        // UTC 02:00 在西五区是前一天 21:00；2024-06-14 是周五
+        let west = FixedOffset::west_opt(5 * 3600).unwrap();
+        let nth_friday = CronExpr::parse("0 0 2 ? * 6#2").unwrap();
+        assert!(nth_friday.matches(at(2024, 6, 15, 2, 0), &west));
+        assert!(!nth_friday.matches(at(2024, 6, 14, 2, 0), &west));
+        assert_eq!(
+            nth_friday.next_after(at(2024, 6, 1, 0, 0), &west),
+            Some(at(2024, 6, 15, 2, 0))
+        );
+    }
+}
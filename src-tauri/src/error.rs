@@ -1,6 +1,22 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// 请求中单个字段的校验错误，field 为字段路径，如 `windows[0].end`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppError {
     DataAccess(String),
@@ -9,6 +25,27 @@ pub enum AppError {
     Scheduler(String),
     Persistence(String),
     Internal(String),
+    /// 一个或多个字段未通过校验
+    InvalidFields(Vec<FieldError>),
+}
+
+impl AppError {
+    /// 不带错误类别前缀的错误信息
+    pub fn detail(&self) -> String {
+        match self {
+            AppError::DataAccess(msg)
+            | AppError::Validation(msg)
+            | AppError::NotFound(msg)
+            | AppError::Scheduler(msg)
+            | AppError::Persistence(msg)
+            | AppError::Internal(msg) => msg.clone(),
+            AppError::InvalidFields(errors) => errors
+                .iter()
+                .map(|error| format!("{}: {}", error.field, error.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
 }
 
 impl fmt::Display for AppError {
@@ -20,6 +57,7 @@ impl fmt::Display for AppError {
             AppError::Scheduler(msg) => write!(f, "Scheduler error: {}", msg),
             AppError::Persistence(msg) => write!(f, "Persistence error: {}", msg),
             AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
+            AppError::InvalidFields(_) => write!(f, "Validation error: {}", self.detail()),
         }
     }
 }
@@ -32,4 +70,22 @@ impl From<AppError> for String {
     }
 }
 
+/// 返回给前端的命令错误：字段校验错误序列化为 `{ "fields": [...] }`，
+/// 便于表单标注到对应字段，其他错误仍为字符串
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CommandError {
+    Message(String),
+    Fields { fields: Vec<FieldError> },
+}
+
+impl From<AppError> for CommandError {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::InvalidFields(fields) => CommandError::Fields { fields },
+            error => CommandError::Message(error.to_string()),
+        }
+    }
+}
+
 pub type AppResult<T> = Result<T, AppError>;
@@ -1,19 +1,31 @@
+mod attachments;
 mod commands;
+mod cron_expr;
 mod error;
 mod models;
 mod repository;
 mod scheduler;
 mod service;
 mod utils;
+mod validation;
 
+use attachments::AttachmentStore;
 use commands::AppState;
-use repository::{InMemoryRepository, PersistenceManager};
+use models::Context;
+use repository::{DataRepository, InMemoryRepository, PersistenceManager};
 use scheduler::ReminderScheduler;
 use service::ReminderService;
 use std::sync::Arc;
-use tauri::{tray::{TrayIconBuilder, TrayIconEvent}, menu::{MenuBuilder, MenuItemBuilder}, Manager, WindowEvent};
+use tauri::{
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
+    tray::{TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager, WindowEvent, Wry,
+};
 use tokio::sync::RwLock;
 
+/// 托盘中切换情境的菜单项 ID 前缀，后接情境 ID，为空表示不按情境过滤
+const CONTEXT_MENU_PREFIX: &str = "context:";
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -26,11 +38,11 @@ pub fn run() {
             let persistence = Arc::new(PersistenceManager::new(app.handle().clone()));
             
             // 加载数据
-            let (groups, reminders) = persistence.load_data()
+            let data = persistence.load_data()
                 .map_err(|e| format!("Failed to load data: {}", e))?;
 
             // 创建仓库
-            let repository = Arc::new(InMemoryRepository::new(groups, reminders.clone()));
+            let repository = Arc::new(InMemoryRepository::new(data));
 
             // 创建调度器
             let scheduler = Arc::new(RwLock::new(ReminderScheduler::new(
@@ -38,11 +50,15 @@ pub fn run() {
                 Arc::clone(&repository) as Arc<dyn repository::DataRepository>,
             )));
 
+            // 附件文件存放在应用数据目录
+            let attachments = Arc::new(AttachmentStore::new(app.handle().clone()));
+
             // 创建服务
             let service = Arc::new(ReminderService::new(
                 Arc::clone(&repository) as Arc<dyn repository::DataRepository>,
                 Arc::clone(&scheduler),
                 Arc::clone(&persistence),
+                attachments,
             ));
 
             // 创建应用状态
@@ -50,15 +66,9 @@ pub fn run() {
             app.manage(app_state);
 
             // // 创建托盘菜单
-            let show_item = MenuItemBuilder::with_id("show", "显示窗口").build(app)?;
-            let hide_item = MenuItemBuilder::with_id("hide", "隐藏窗口").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "退出应用").build(app)?;
-            
-            let menu = MenuBuilder::new(app)
-                .items(&[&show_item, &hide_item])
-                .separator()
-                .item(&quit_item)
-                .build()?;
+            let contexts = repository.get_contexts()?;
+            let active_context = repository.get_settings()?.active_context_id;
+            let menu = build_tray_menu(app.handle(), &contexts, active_context.as_deref())?;
 
             // 创建托盘图标
             let _tray = TrayIconBuilder::with_id("main")
@@ -108,6 +118,22 @@ pub fn run() {
                             });
                             app.exit(0);
                         }
+                        id if id.starts_with(CONTEXT_MENU_PREFIX) => {
+                            // 切换当前情境
+                            let context_id = id
+                                .strip_prefix(CONTEXT_MENU_PREFIX)
+                                .filter(|id| !id.is_empty())
+                                .map(str::to_string);
+                            let app_state = app.state::<AppState>();
+                            let service = Arc::clone(&app_state.service);
+                            let rt = tokio::runtime::Runtime::new().unwrap();
+                            rt.block_on(async {
+                                if let Err(e) = service.set_active_context(context_id).await {
+                                    eprintln!("Failed to switch context: {}", e);
+                                }
+                                refresh_tray_menu(app, &service).await;
+                            });
+                        }
                         _ => {}
                     }
                 })
@@ -134,6 +160,24 @@ pub fn run() {
                     } else {
                         println!("Successfully restored reminder jobs");
                     }
+
+                    // 定期检查暂停和假期模式是否到期（包括应用关闭期间已到期的），
+                    // 清理过期的触发记录并保存调度线程的修改
+                    loop {
+                        if let Err(e) = service_clone.resume_expired_pauses().await {
+                            eprintln!("Failed to resume expired pauses: {}", e);
+                        }
+                        if let Err(e) = service_clone.end_expired_vacation().await {
+                            eprintln!("Failed to end expired vacation: {}", e);
+                        }
+                        if let Err(e) = service_clone.prune_occurrence_log().await {
+                            eprintln!("Failed to prune occurrence log: {}", e);
+                        }
+                        if let Err(e) = service_clone.save_unsaved_changes().await {
+                            eprintln!("Failed to save scheduler changes: {}", e);
+                        }
+                        tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+                    }
                 });
             });
 
@@ -146,10 +190,60 @@ pub fn run() {
             commands::get_reminders,
             commands::create_reminder,
             commands::update_reminder,
+            commands::duplicate_reminder,
+            commands::duplicate_group,
             commands::pause_reminder,
             commands::resume_reminder,
             commands::cancel_reminder,
+            commands::complete_reminder,
+            commands::acknowledge_reminder,
+            commands::query_reminders,
+            commands::add_checklist_item,
+            commands::toggle_checklist_item,
+            commands::reorder_checklist,
+            commands::remove_checklist_item,
+            commands::add_url_attachment,
+            commands::add_file_attachment,
+            commands::remove_attachment,
+            commands::open_attachment,
             commands::delete_reminder,
+            commands::skip_next_occurrence,
+            commands::skip_occurrence_date,
+            commands::move_occurrence,
+            commands::clear_occurrence_overrides,
+            commands::get_occurrences,
+            commands::get_occurrence_log,
+            commands::mark_occurrence,
+            commands::get_stats,
+            commands::set_history_retention,
+            commands::get_scheduler_diagnostics,
+            commands::reconcile_scheduler,
+            commands::parse_cron_expression,
+            commands::format_cron_expression,
+            commands::get_routines,
+            commands::create_routine,
+            commands::update_routine,
+            commands::delete_routine,
+            commands::start_routine,
+            commands::stop_routine,
+            commands::acknowledge_routine_step,
+            commands::get_contexts,
+            commands::create_context,
+            commands::delete_context,
+            commands::set_group_contexts,
+            commands::get_settings,
+            commands::set_active_context,
+            commands::start_vacation,
+            commands::end_vacation,
+            commands::reactivate_after_vacation,
+            commands::dismiss_after_vacation,
+            commands::set_quiet_hours,
+            commands::get_tags,
+            commands::create_tag,
+            commands::rename_tag,
+            commands::merge_tags,
+            commands::delete_tag,
+            commands::apply_tag_action,
         ])
         .on_window_event(|window, event| {
             match event {
@@ -165,6 +259,59 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
+/// 托盘菜单：窗口操作、情境切换和退出
+fn build_tray_menu(
+    app: &AppHandle,
+    contexts: &[Context],
+    active_context: Option<&str>,
+) -> tauri::Result<Menu<Wry>> {
+    let show_item = MenuItemBuilder::with_id("show", "显示窗口").build(app)?;
+    let hide_item = MenuItemBuilder::with_id("hide", "隐藏窗口").build(app)?;
+    let quit_item = MenuItemBuilder::with_id("quit", "退出应用").build(app)?;
+
+    let all_item = CheckMenuItemBuilder::with_id(CONTEXT_MENU_PREFIX, "全部")
+        .checked(active_context.is_none())
+        .build(app)?;
+    let mut context_menu = SubmenuBuilder::new(app, "情境").item(&all_item);
+    for context in contexts {
+        let item = CheckMenuItemBuilder::with_id(
+            format!("{}{}", CONTEXT_MENU_PREFIX, context.id),
+            &context.name,
+        )
+        .checked(active_context == Some(context.id.as_str()))
+        .build(app)?;
+        context_menu = context_menu.item(&item);
+    }
+    let context_menu = context_menu.build()?;
+
+    MenuBuilder::new(app)
+        .items(&[&show_item, &hide_item])
+        .separator()
+        .item(&context_menu)
+        .separator()
+        .item(&quit_item)
+        .build()
+}
+
+/// 情境变化后重建托盘菜单
+pub(crate) async fn refresh_tray_menu(app: &AppHandle, service: &ReminderService) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let contexts = service.get_contexts().await.unwrap_or_default();
+    let active_context = service
+        .get_settings()
+        .await
+        .map(|settings| settings.active_context_id)
+        .unwrap_or_default();
+
+    let result = build_tray_menu(app, &contexts, active_context.as_deref())
+        .and_then(|menu| tray.set_menu(Some(menu)));
+    if let Err(e) = result {
+        eprintln!("Failed to refresh tray menu: {}", e);
+    }
+}
+
 async fn save_on_exit(service: &ReminderService) -> Result<(), error::AppError> {
     let groups = service.get_groups().await?;
     let reminders = service.get_reminders().await?;
@@ -1,4 +1,10 @@
-use serde::{Deserialize, Serialize};
+use crate::cron_expr::parse_schedule;
+use crate::error::{AppError, AppResult, FieldError};
+use crate::utils::{format_duration, parse_local_datetime};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,18 +12,685 @@ pub struct ReminderGroup {
     pub id: String,
     pub name: String,
     pub color: String,
-    pub start_at: i64,
+    /// 旧数据中名为 start_at
+    #[serde(alias = "start_at")]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    /// 分组所属的情境，组内未单独设置情境的提醒沿用此设置
+    #[serde(default)]
+    pub contexts: Vec<String>,
 }
 
 impl ReminderGroup {
     pub fn new(name: String, color: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
         Self {
             id: Uuid::new_v4().to_string(),
             name,
             color,
-            start_at: chrono::Utc::now().timestamp(),
+            created_at: now,
+            updated_at: now,
+            contexts: Vec::new(),
         }
     }
+
+    /// 复制分组设置，新分组使用新的 ID 和创建时间
+    pub fn duplicate(&self) -> Self {
+        let mut copy = Self::new(self.name.clone(), self.color.clone());
+        copy.contexts = self.contexts.clone();
+        copy
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now().timestamp();
+    }
+}
+
+/// 旧数据没有修改时间时以创建时间补齐，返回是否有修改
+pub fn backfill_updated_at(updated_at: &mut i64, created_at: i64) -> bool {
+    if *updated_at != 0 {
+        return false;
+    }
+    *updated_at = created_at;
+    true
+}
+
+/// 用户定义的情境，例如在家、上班、出差
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Context {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl Context {
+    pub fn new(name: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// 可同时分配给多个提醒的标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl Tag {
+    pub fn new(name: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now().timestamp();
+    }
+
+    /// 规范化标签名：去掉首尾空白和开头的 `#`
+    pub fn normalize_name(name: &str) -> String {
+        name.trim().trim_start_matches('#').trim().to_string()
+    }
+}
+
+/// 应用级设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// 当前情境，为空表示不按情境过滤
+    #[serde(default)]
+    pub active_context_id: Option<String>,
+    /// 进行中的假期模式
+    #[serde(default)]
+    pub vacation: Option<VacationMode>,
+    /// 假期结束后等待用户处理的汇总
+    #[serde(default)]
+    pub vacation_summary: Option<VacationSummary>,
+    /// 免打扰时段，只有紧急提醒会通知
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    pub history_retention: HistoryRetention,
+}
+
+impl AppSettings {
+    /// now 时是否处于假期模式
+    pub fn is_on_vacation(&self, now: i64) -> bool {
+        self.vacation
+            .as_ref()
+            .is_some_and(|vacation| vacation.until.is_none_or(|until| now < until))
+    }
+}
+
+/// 每天的免打扰时段（`%H:%M`），结束时间早于开始时间表示跨午夜
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    pub fn validate(&self) -> AppResult<()> {
+        match self.times() {
+            Some((start, end)) if start != end => Ok(()),
+            Some(_) => Err(AppError::Validation(
+                "Quiet hours must not start and end at the same time".to_string(),
+            )),
+            None => Err(AppError::Validation(format!(
+                "Invalid quiet hours {}-{}, expected HH:MM",
+                self.start, self.end
+            ))),
+        }
+    }
+
+    fn times(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let parse = |value: &str| NaiveTime::parse_from_str(value, "%H:%M").ok();
+        Some((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.times() {
+            Some((start, end)) if start <= end => start <= time && time < end,
+            Some((start, end)) => time >= start || time < end,
+            None => false,
+        }
+    }
+}
+
+/// 假期模式：暂停所有通知，并记录期间本应触发的提醒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VacationMode {
+    pub started_at: i64,
+    /// 自动结束时间，为空表示手动关闭
+    pub until: Option<i64>,
+    #[serde(default)]
+    pub missed: Vec<MissedReminder>,
+}
+
+impl VacationMode {
+    pub fn new(started_at: i64, until: Option<i64>) -> Self {
+        Self {
+            started_at,
+            until,
+            missed: Vec::new(),
+        }
+    }
+
+    /// 记录一次被假期模式拦下的触发
+    pub fn record_missed(&mut self, reminder: &Reminder, occurrence_at: i64) {
+        match self.missed.iter_mut().find(|m| m.reminder_id == reminder.id) {
+            Some(missed) => {
+                missed.missed_count += 1;
+                missed.last_missed_at = occurrence_at;
+            }
+            None => self.missed.push(MissedReminder {
+                reminder_id: reminder.id.clone(),
+                title: reminder.title.clone(),
+                missed_count: 1,
+                first_missed_at: occurrence_at,
+                last_missed_at: occurrence_at,
+            }),
+        }
+    }
+}
+
+/// 假期期间某个提醒错过的触发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedReminder {
+    pub reminder_id: String,
+    pub title: String,
+    pub missed_count: u32,
+    pub first_missed_at: i64,
+    pub last_missed_at: i64,
+}
+
+/// 假期结束后的汇总，列出的提醒在用户处理前保持暂停
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VacationSummary {
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub reminders: Vec<MissedReminder>,
+}
+
+/// 提醒的生命周期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderStatus {
+    #[default]
+    Active,
+    Paused,
+    Cancelled,
+    Completed,
+    Deleted,
+}
+
+impl ReminderStatus {
+    /// 判断是否允许迁移到目标状态
+    pub fn can_transition_to(self, next: ReminderStatus) -> bool {
+        use ReminderStatus::*;
+        matches!(
+            (self, next),
+            (Active, Paused)
+                | (Active, Cancelled)
+                | (Active, Completed)
+                | (Active, Deleted)
+                | (Paused, Active)
+                | (Paused, Cancelled)
+                | (Paused, Deleted)
+                | (Cancelled, Deleted)
+                | (Completed, Active)
+                | (Completed, Deleted)
+        )
+    }
+}
+
+impl fmt::Display for ReminderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReminderStatus::Active => "active",
+            ReminderStatus::Paused => "paused",
+            ReminderStatus::Cancelled => "cancelled",
+            ReminderStatus::Completed => "completed",
+            ReminderStatus::Deleted => "deleted",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 对重复提醒中单次触发的调整，不改变重复规则本身
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OccurrenceOverride {
+    /// 跳过计划在该时间（Unix 时间戳，秒）的一次触发
+    Skip { occurrence_at: i64 },
+    /// 跳过某一天（本地日期）的所有触发
+    SkipDate { date: NaiveDate },
+    /// 把计划在 occurrence_at 的一次触发改到 new_time
+    Move { occurrence_at: i64, new_time: i64 },
+}
+
+impl OccurrenceOverride {
+    /// 该调整是否作用于指定的计划触发时间
+    pub fn applies_to(&self, occurrence_at: i64) -> bool {
+        match self {
+            OccurrenceOverride::Skip { occurrence_at: at }
+            | OccurrenceOverride::Move { occurrence_at: at, .. } => *at == occurrence_at,
+            OccurrenceOverride::SkipDate { date } => Local
+                .timestamp_opt(occurrence_at, 0)
+                .single()
+                .is_some_and(|time| time.date_naive() == *date),
+        }
+    }
+
+    /// 调整是否已经过期，可以清理；保留一分钟余量，避免与正在执行的任务竞争
+    pub fn is_expired(&self, now: i64) -> bool {
+        let grace = now - 60;
+        match self {
+            OccurrenceOverride::Skip { occurrence_at } => *occurrence_at < grace,
+            OccurrenceOverride::SkipDate { date } => *date < Local::now().date_naive(),
+            OccurrenceOverride::Move {
+                occurrence_at,
+                new_time,
+            } => *occurrence_at < grace && *new_time < grace,
+        }
+    }
+}
+
+/// 提醒的调度计划，反序列化时补齐截止提醒的默认值并校验取值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", try_from = "RawSchedule")]
+pub enum Schedule {
+    /// 按 cron 表达式（或英文描述）在固定时间触发
+    Cron { expression: String },
+    /// 在指定时刻触发一次，触发后提醒完成
+    Once { at: i64 },
+    /// 每隔若干分钟触发一次，从上次触发（或创建时）开始计时
+    Interval { minutes: u32 },
+    /// 上次完成后间隔若干天再次提醒
+    AfterCompletion { interval_days: u32 },
+    /// 截止提醒：在截止前若干分钟分阶段提醒，逾期后按间隔重复催促直到完成
+    Deadline {
+        due_at: i64,
+        /// 从早到晚排列（分钟数从大到小）
+        alert_minutes_before: Vec<u32>,
+        overdue_repeat_minutes: u32,
+    },
+    /// 旧数据中没有有效 cron 表达式的提醒：不会触发，设置计划后才能恢复
+    Unscheduled,
+}
+
+/// 截止提醒默认的提醒阶段：7 天、1 天、2 小时和到期时
+pub const DEFAULT_DEADLINE_ALERTS: [u32; 4] = [7 * 24 * 60, 24 * 60, 120, 0];
+/// 逾期后默认每小时催促一次
+pub const DEFAULT_OVERDUE_REPEAT_MINUTES: u32 = 60;
+
+/// 前端提交的调度计划，字段不做类型限制，由 validation 解析并报告具体字段的错误
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleInput {
+    /// cron、once、interval、after_completion 或 deadline
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub expression: Option<String>,
+    pub at: Option<i64>,
+    pub minutes: Option<i64>,
+    pub interval_days: Option<i64>,
+    pub due_at: Option<i64>,
+    pub alert_minutes_before: Option<Vec<i64>>,
+    pub overdue_repeat_minutes: Option<i64>,
+}
+
+/// 反序列化用的调度计划，截止提醒可省略阶段和催促间隔
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RawSchedule {
+    Cron {
+        expression: String,
+    },
+    Once {
+        at: i64,
+    },
+    Interval {
+        minutes: u32,
+    },
+    AfterCompletion {
+        interval_days: u32,
+    },
+    Deadline {
+        due_at: i64,
+        alert_minutes_before: Option<Vec<u32>>,
+        overdue_repeat_minutes: Option<u32>,
+    },
+    Unscheduled,
+}
+
+impl TryFrom<RawSchedule> for Schedule {
+    type Error = AppError;
+
+    fn try_from(raw: RawSchedule) -> AppResult<Self> {
+        let schedule = match raw {
+            RawSchedule::Cron { expression } => Schedule::Cron {
+                expression: expression.trim().to_string(),
+            },
+            RawSchedule::Once { at } => Schedule::Once { at },
+            RawSchedule::Interval { minutes } => Schedule::Interval { minutes },
+            RawSchedule::AfterCompletion { interval_days } => {
+                Schedule::AfterCompletion { interval_days }
+            }
+            RawSchedule::Deadline {
+                due_at,
+                alert_minutes_before,
+                overdue_repeat_minutes,
+            } => Schedule::deadline(due_at, alert_minutes_before, overdue_repeat_minutes),
+            RawSchedule::Unscheduled => Schedule::Unscheduled,
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+}
+
+impl Schedule {
+    /// 截止提醒，未设置的阶段和催促间隔使用默认值，阶段按从早到晚排列
+    pub fn deadline(
+        due_at: i64,
+        alert_minutes_before: Option<Vec<u32>>,
+        overdue_repeat_minutes: Option<u32>,
+    ) -> Self {
+        let mut alert_minutes_before =
+            alert_minutes_before.unwrap_or_else(|| DEFAULT_DEADLINE_ALERTS.to_vec());
+        alert_minutes_before.sort_unstable_by(|a, b| b.cmp(a));
+        alert_minutes_before.dedup();
+        Schedule::Deadline {
+            due_at,
+            alert_minutes_before,
+            overdue_repeat_minutes: overdue_repeat_minutes
+                .unwrap_or(DEFAULT_OVERDUE_REPEAT_MINUTES),
+        }
+    }
+
+    /// 检查调度计划的取值，前端输入和已保存的数据都经过此检查；
+    /// 错误按字段返回，字段名相对于调度计划，如 `expression`
+    pub fn validate(&self) -> AppResult<()> {
+        let mut errors = Vec::new();
+        let mut check_time = |field: &str, at: i64| {
+            if at <= 0 || chrono::DateTime::from_timestamp(at, 0).is_none() {
+                errors.push(FieldError::new(field, format!("Invalid time {}", at)));
+            }
+        };
+        match self {
+            Schedule::Cron { expression } if expression.is_empty() => {
+                errors.push(FieldError::new("expression", "Cron expression is required"));
+            }
+            Schedule::Cron { expression } => {
+                if let Err(e) = parse_schedule(expression) {
+                    errors.push(FieldError::new("expression", e.detail()));
+                }
+            }
+            Schedule::Once { at } => check_time("at", *at),
+            Schedule::Deadline { due_at, .. } => check_time("due_at", *due_at),
+            Schedule::Interval { minutes: 0 } => {
+                errors.push(FieldError::new("minutes", "Interval must be at least one minute"));
+            }
+            Schedule::AfterCompletion { interval_days: 0 } => errors.push(FieldError::new(
+                "interval_days",
+                "Repeat interval must be at least one day",
+            )),
+            Schedule::Interval { .. }
+            | Schedule::AfterCompletion { .. }
+            | Schedule::Unscheduled => {}
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+
+    /// 是否会重复触发，重复提醒每次触发时重置清单
+    pub fn is_recurring(&self) -> bool {
+        matches!(
+            self,
+            Schedule::Cron { .. } | Schedule::Interval { .. } | Schedule::AfterCompletion { .. }
+        )
+    }
+}
+
+/// 截止提醒在某一时刻应发出的通知
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlineAlert {
+    /// 到达某个提醒阶段（截止前的分钟数）
+    Stage(u32),
+    Overdue,
+}
+
+/// 提醒优先级，决定通知方式、是否无视免打扰时段以及是否需要确认
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl Priority {
+    /// 通知标题前缀，桌面通知不支持紧急程度时用于区分
+    pub fn title_prefix(self) -> &'static str {
+        match self {
+            Priority::Critical => "【紧急】",
+            Priority::High => "【重要】",
+            Priority::Low | Priority::Normal => "",
+        }
+    }
+
+    /// 低优先级的通知不播放声音
+    pub fn is_silent(self) -> bool {
+        self == Priority::Low
+    }
+
+    pub fn bypasses_quiet_hours(self) -> bool {
+        self == Priority::Critical
+    }
+
+    /// 通知后需要用户确认，未确认时托盘会标记
+    pub fn requires_acknowledgement(self) -> bool {
+        self >= Priority::High
+    }
+
+    /// 未确认时的重复通知：(间隔分钟, 最多次数)
+    pub fn renotify_policy(self) -> Option<(u32, u32)> {
+        match self {
+            Priority::Critical => Some((5, 3)),
+            Priority::High => Some((15, 1)),
+            Priority::Low | Priority::Normal => None,
+        }
+    }
+}
+
+/// 提醒中的清单项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub text: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Url,
+    File,
+}
+
+/// 提醒附件：链接或已复制到应用数据目录的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub name: String,
+    pub kind: AttachmentKind,
+    /// 链接地址，或附件文件在应用数据目录中的路径
+    pub location: String,
+    pub added_at: i64,
+}
+
+impl Attachment {
+    pub fn url(url: &str, name: Option<String>, added_at: i64) -> AppResult<Self> {
+        let url = url.trim();
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or_default();
+        // 单个字母视为 Windows 盘符
+        let valid_scheme = scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        // 本地文件应作为文件附件添加，以便复制到应用数据目录
+        if !valid_scheme || scheme.eq_ignore_ascii_case("file") || url.len() == scheme.len() + 1 {
+            return Err(AppError::Validation(format!("Invalid attachment url: {}", url)));
+        }
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            name: Self::display_name(name, url),
+            kind: AttachmentKind::Url,
+            location: url.to_string(),
+            added_at,
+        })
+    }
+
+    /// 文件附件，location 由调用方复制文件后填入
+    pub fn file(id: String, name: String, location: String, added_at: i64) -> Self {
+        Self {
+            id,
+            name,
+            kind: AttachmentKind::File,
+            location,
+            added_at,
+        }
+    }
+
+    /// 未指定名称时使用链接或文件名
+    pub fn display_name(name: Option<String>, fallback: &str) -> String {
+        name.map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| fallback.to_string())
+    }
+}
+
+/// 提醒触发时发给前端的附件列表，便于从通知中打开
+#[derive(Debug, Clone, Serialize)]
+pub struct ReminderAttachmentsEvent {
+    pub reminder_id: String,
+    pub title: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// 提醒生效的时间窗口：在指定的星期几，从 start 到 end（含两端），
+/// 序列化为 `%H:%M`，反序列化时校验星期和起止时间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "TimeWindowInput")]
+pub struct TimeWindow {
+    /// 1-7 表示周一至周日，为空表示每天
+    pub weekdays: Vec<u32>,
+    #[serde(serialize_with = "serialize_hour_minute")]
+    pub start: NaiveTime,
+    #[serde(serialize_with = "serialize_hour_minute")]
+    pub end: NaiveTime,
+}
+
+/// 时间窗口的原始形式，时间为 `HH:MM` 字符串
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindowInput {
+    #[serde(default)]
+    pub weekdays: Vec<u32>,
+    pub start: String,
+    pub end: String,
+}
+
+impl TryFrom<TimeWindowInput> for TimeWindow {
+    type Error = AppError;
+
+    fn try_from(raw: TimeWindowInput) -> AppResult<Self> {
+        let window = Self {
+            weekdays: raw.weekdays,
+            start: parse_hour_minute(&raw.start)?,
+            end: parse_hour_minute(&raw.end)?,
+        };
+        window.validate()?;
+        Ok(window)
+    }
+}
+
+pub fn parse_hour_minute(value: &str) -> AppResult<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| AppError::Validation(format!("Invalid time {}, expected HH:MM", value)))
+}
+
+fn serialize_hour_minute<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&time.format("%H:%M").to_string())
+}
+
+impl TimeWindow {
+    /// 检查星期和起止时间，错误按字段返回，字段名相对于窗口
+    pub fn validate(&self) -> AppResult<()> {
+        let mut errors = Vec::new();
+        if let Err(e) = Self::validate_weekdays(&self.weekdays) {
+            errors.push(FieldError::new("weekdays", e.detail()));
+        }
+        if self.start >= self.end {
+            errors.push(FieldError::new("end", "Time window must end after it starts"));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+
+    pub fn validate_weekdays(weekdays: &[u32]) -> AppResult<()> {
+        match weekdays.iter().find(|day| !(1..=7).contains(*day)) {
+            Some(weekday) => Err(AppError::Validation(format!(
+                "Invalid weekday {}, expected 1 (Monday) to 7 (Sunday)",
+                weekday
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        self.start <= time && time <= self.end
+    }
+
+    pub fn applies_on(&self, weekday: Weekday) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&weekday.number_from_monday())
+    }
+
+    /// 两个窗口是否在某一天有重叠
+    pub fn overlaps(&self, other: &TimeWindow) -> bool {
+        let shares_day = self.weekdays.is_empty()
+            || other.weekdays.is_empty()
+            || self.weekdays.iter().any(|day| other.weekdays.contains(day));
+        shares_day && self.start <= other.end && other.start <= self.end
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,15 +699,93 @@ pub struct Reminder {
     pub title: String,
     pub color: String,
     pub group_id: String,
-    pub cron_expression: Option<String>,
-    pub start_at: Option<String>,
+    pub schedule: Schedule,
     pub last_triggered: Option<i64>,
+    #[serde(default)]
+    pub status: ReminderStatus,
+    // 以下三个标记由 status 同步维护，保留以兼容旧数据和前端
     pub is_cancelled: bool,
     pub is_deleted: bool,
     pub is_paused: bool,
     pub description: Option<String>,
-    pub created_at: Option<String>,
-    pub end_at: Option<String>,
+    /// 旧数据中可能为空或字符串，加载时补齐
+    #[serde(default, deserialize_with = "deserialize_legacy_timestamp")]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    /// 暂停截止时间（Unix 时间戳，秒），为空表示无限期暂停
+    #[serde(default)]
+    pub paused_until: Option<i64>,
+    /// 自动恢复时是否发送通知
+    #[serde(default)]
+    pub notify_on_resume: bool,
+    #[serde(default)]
+    pub occurrence_overrides: Vec<OccurrenceOverride>,
+    /// 用户最近一次确认完成的时间
+    #[serde(default)]
+    pub last_completed_at: Option<i64>,
+    /// 按完成时间计算的下一次提醒时间，仅用于 AfterCompletion
+    #[serde(default)]
+    pub next_due_at: Option<i64>,
+    /// 已发出的截止提醒阶段，仅用于 Deadline
+    #[serde(default)]
+    pub sent_deadline_alerts: Vec<u32>,
+    /// 按星期设置的时间窗口，为空表示不限制
+    #[serde(default)]
+    pub windows: Vec<TimeWindow>,
+    /// 每天最多通知次数，为空表示不限制
+    #[serde(default)]
+    pub max_per_day: Option<u32>,
+    /// 当天（本地日期）已通知的次数，跨天后重新计数
+    #[serde(default)]
+    pub daily_fires: Option<DailyFireCount>,
+    /// 提醒所属的情境，为空时沿用分组的情境
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// 需要确认的通知发出时间，确认后清空
+    #[serde(default)]
+    pub awaiting_ack_since: Option<i64>,
+    /// 未确认期间已重复通知的次数
+    #[serde(default)]
+    pub renotify_count: u32,
+    /// 标签 ID
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 有序清单，重复提醒每次触发时重置
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyTimestamp {
+    Seconds(i64),
+    Text(String),
+}
+
+/// 兼容旧数据的时间：接受时间戳、日期字符串或 null，无法识别时为 0（待补齐）
+fn deserialize_legacy_timestamp<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<LegacyTimestamp>::deserialize(deserializer)? {
+        Some(LegacyTimestamp::Seconds(seconds)) => seconds,
+        Some(LegacyTimestamp::Text(text)) => parse_local_datetime(&text)
+            .map(|time| time.timestamp())
+            .unwrap_or(0),
+        None => 0,
+    })
+}
+
+/// 某一天的通知计数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyFireCount {
+    pub date: NaiveDate,
+    pub count: u32,
 }
 
 impl Reminder {
@@ -42,112 +793,1562 @@ impl Reminder {
         title: String,
         color: String,
         group_id: String,
-        cron_expression: Option<String>,
+        schedule: Schedule,
         description: Option<String>,
-        start_at: Option<String>,
-        end_at: Option<String>,
     ) -> Self {
-        Self {
+        let now = chrono::Utc::now().timestamp();
+        let mut reminder = Self {
             id: Uuid::new_v4().to_string(),
             title,
             color,
             group_id,
-            start_at,
+            schedule: schedule.clone(),
             last_triggered: None,
+            status: ReminderStatus::Active,
             is_cancelled: false,
             is_deleted: false,
             is_paused: false,
-            cron_expression,
             description,
-            created_at: None,
-            end_at,
+            created_at: now,
+            updated_at: now,
+            paused_until: None,
+            notify_on_resume: false,
+            occurrence_overrides: Vec::new(),
+            last_completed_at: None,
+            next_due_at: None,
+            sent_deadline_alerts: Vec::new(),
+            windows: Vec::new(),
+            max_per_day: None,
+            daily_fires: None,
+            contexts: Vec::new(),
+            priority: Priority::Normal,
+            awaiting_ack_since: None,
+            renotify_count: 0,
+            tags: Vec::new(),
+            checklist: Vec::new(),
+            attachments: Vec::new(),
+        };
+        reminder.set_schedule(schedule);
+        reminder
+    }
+
+    /// 复制提醒设置到 group_id 下的新提醒：触发、完成、确认、暂停等运行状态和单次调整重新开始，
+    /// 清单项重置为未完成；附件由调用方复制
+    pub fn duplicate(&self, group_id: String) -> AppResult<Self> {
+        let mut copy = Self::new(
+            self.title.clone(),
+            self.color.clone(),
+            group_id,
+            self.schedule.clone(),
+            self.description.clone(),
+        );
+        copy.windows = self.windows.clone();
+        copy.max_per_day = self.max_per_day;
+        copy.contexts = self.contexts.clone();
+        copy.priority = self.priority;
+        copy.tags = self.tags.clone();
+        for item in &self.checklist {
+            copy.add_checklist_item(&item.text)?;
         }
+        Ok(copy)
     }
 
     pub fn is_active(&self) -> bool {
-        !self.is_cancelled && !self.is_deleted && !self.is_paused
+        self.status == ReminderStatus::Active
+    }
+
+    /// 迁移到新状态，非法迁移返回校验错误
+    pub fn transition_to(&mut self, next: ReminderStatus) -> AppResult<()> {
+        if !self.status.can_transition_to(next) {
+            return Err(AppError::Validation(format!(
+                "Cannot change reminder {} from {} to {}",
+                self.id, self.status, next
+            )));
+        }
+        if next == ReminderStatus::Active && self.schedule == Schedule::Unscheduled {
+            return Err(AppError::Validation(format!(
+                "Reminder {} has no schedule, set one before resuming it",
+                self.id
+            )));
+        }
+
+        self.status = next;
+        self.is_paused = next == ReminderStatus::Paused;
+        self.is_cancelled = next == ReminderStatus::Cancelled;
+        self.is_deleted = next == ReminderStatus::Deleted;
+        if next != ReminderStatus::Paused {
+            self.paused_until = None;
+            self.notify_on_resume = false;
+        }
+        Ok(())
     }
 
-    pub fn cancel(&mut self) {
-        self.is_cancelled = true;
+    /// 记录用户修改的时间，调度触发等运行状态变化不算修改
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now().timestamp();
     }
 
-    pub fn delete(&mut self) {
-        self.is_deleted = true;
+    /// 补齐旧数据的创建时间（取最早的活动时间，都没有时取 fallback）和修改时间，返回是否有修改
+    pub fn backfill_timestamps(&mut self, first_logged_at: Option<i64>, fallback: i64) -> bool {
+        let mut changed = false;
+        if self.created_at == 0 {
+            self.created_at = [self.last_triggered, self.last_completed_at, first_logged_at]
+                .into_iter()
+                .flatten()
+                .fold(fallback, i64::min);
+            changed = true;
+        }
+        backfill_updated_at(&mut self.updated_at, self.created_at) || changed
     }
 
-    pub fn pause(&mut self) {
-        self.is_paused = true;
+    /// 根据旧数据中的布尔标记推导状态
+    pub fn migrate_legacy_status(&mut self) {
+        if self.status != ReminderStatus::Active {
+            return;
+        }
+        self.status = if self.is_deleted {
+            ReminderStatus::Deleted
+        } else if self.is_cancelled {
+            ReminderStatus::Cancelled
+        } else if self.is_paused {
+            ReminderStatus::Paused
+        } else {
+            ReminderStatus::Active
+        };
     }
 
-    pub fn resume(&mut self) {
-        self.is_paused = false;
+    pub fn cancel(&mut self) -> AppResult<()> {
+        self.transition_to(ReminderStatus::Cancelled)
     }
 
-    pub fn update_last_triggered(&mut self) {
-        self.last_triggered = Some(chrono::Utc::now().timestamp());
+    pub fn delete(&mut self) -> AppResult<()> {
+        self.transition_to(ReminderStatus::Deleted)
     }
 
-    pub fn update(
-        &mut self,
-        title: Option<String>,
-        color: Option<String>,
-        cron_expression: Option<String>,
-        description: Option<String>,
-        start_at: Option<String>,
-        end_at: Option<String>,
-    ) -> bool {
-        let mut schedule_changed = false;
+    pub fn complete(&mut self) -> AppResult<()> {
+        self.transition_to(ReminderStatus::Completed)
+    }
 
-        if let Some(title) = title {
-            self.title = title;
+    /// 暂停提醒；已暂停时只更新恢复时间和恢复通知
+    pub fn pause(&mut self, until: Option<i64>, notify_on_resume: bool) -> AppResult<()> {
+        if self.status != ReminderStatus::Paused {
+            self.transition_to(ReminderStatus::Paused)?;
         }
-        if let Some(color) = color {
-            self.color = color;
+        self.paused_until = until;
+        self.notify_on_resume = notify_on_resume;
+        Ok(())
+    }
+
+    pub fn resume(&mut self) -> AppResult<()> {
+        self.transition_to(ReminderStatus::Active)
+    }
+
+    /// 暂停是否已到期，需要自动恢复
+    pub fn is_pause_expired(&self, now: i64) -> bool {
+        self.status == ReminderStatus::Paused && self.paused_until.is_some_and(|until| until <= now)
+    }
+
+    /// 检查本地时间是否落在时间窗口内，未设置窗口时总是允许
+    pub fn window_skip_reason(&self, at: NaiveDateTime) -> Option<SkipReason> {
+        if self.windows.is_empty() {
+            return None;
         }
-        if let Some(cron_expression) = cron_expression {
-            self.cron_expression = Some(cron_expression);
-            schedule_changed = true;
+
+        let time = at.time();
+        let today: Vec<&TimeWindow> = self
+            .windows
+            .iter()
+            .filter(|window| window.applies_on(at.weekday()))
+            .collect();
+        if today.iter().any(|window| window.contains(time)) {
+            return None;
         }
-        if let Some(description) = description {
-            self.description = Some(description);
+
+        if !today.is_empty() && today.iter().all(|window| time < window.start) {
+            Some(SkipReason::BeforeStart)
+        } else if !today.is_empty() && today.iter().all(|window| time > window.end) {
+            Some(SkipReason::AfterEnd)
+        } else {
+            Some(SkipReason::OutsideWindow)
         }
-        if let Some(start_at) = start_at {
-            self.start_at = Some(start_at);
-            schedule_changed = true;
+    }
+
+    /// 设置按星期的时间窗口，传空列表表示清除
+    pub fn set_windows(&mut self, windows: Vec<TimeWindow>) -> AppResult<()> {
+        for window in &windows {
+            window.validate()?;
         }
-        if let Some(end_at) = end_at {
-            self.end_at = Some(end_at);
-            schedule_changed = true;
+        self.windows = windows;
+        Ok(())
+    }
+
+    /// 查找作用于指定计划触发时间的调整
+    pub fn override_for(&self, occurrence_at: i64) -> Option<&OccurrenceOverride> {
+        self.occurrence_overrides
+            .iter()
+            .find(|o| o.applies_to(occurrence_at))
+    }
+
+    pub fn add_occurrence_override(&mut self, occurrence_override: OccurrenceOverride) {
+        let now = chrono::Utc::now().timestamp();
+        self.occurrence_overrides.retain(|o| !o.is_expired(now));
+        self.occurrence_overrides.push(occurrence_override);
+    }
+
+    pub fn update_last_triggered(&mut self) {
+        self.last_triggered = Some(chrono::Utc::now().timestamp());
+    }
+
+    /// 指定日期已通知的次数
+    pub fn fires_on(&self, date: NaiveDate) -> u32 {
+        self.daily_fires
+            .as_ref()
+            .filter(|fires| fires.date == date)
+            .map_or(0, |fires| fires.count)
+    }
+
+    pub fn daily_cap_reached(&self, date: NaiveDate) -> bool {
+        self.max_per_day
+            .is_some_and(|max_per_day| self.fires_on(date) >= max_per_day)
+    }
+
+    /// 记录一次成功的通知，日期变化时从零开始计数
+    pub fn record_daily_fire(&mut self, date: NaiveDate) {
+        let count = self.fires_on(date) + 1;
+        self.daily_fires = Some(DailyFireCount { date, count });
+    }
+
+    /// 记录一次成功通知，需要确认的提醒开始等待确认
+    pub fn mark_notified(&mut self, now: i64) {
+        if self.priority.requires_acknowledgement() {
+            self.awaiting_ack_since = Some(now);
+            self.renotify_count = 0;
         }
+    }
 
-        schedule_changed
+    pub fn acknowledge(&mut self) {
+        self.awaiting_ack_since = None;
+        self.renotify_count = 0;
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CreateReminderRequest {
-    pub title: String,
-    pub color: String,
-    pub group_id: String,
-    pub cron_expression: Option<String>,
-    pub description: Option<String>,
-    pub start_at: Option<String>,
-    pub end_at: Option<String>,
-}
+    /// 未确认的通知是否到了重复通知的时间
+    pub fn is_renotify_due(&self, now: i64) -> bool {
+        let (Some(since), Some((interval_minutes, max_times))) =
+            (self.awaiting_ack_since, self.priority.renotify_policy())
+        else {
+            return false;
+        };
+        let last = self.last_triggered.unwrap_or(since).max(since);
+        self.renotify_count < max_times && now - last >= i64::from(interval_minutes) * 60
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UpdateReminderRequest {
-    pub title: Option<String>,
-    pub color: Option<String>,
-    pub cron_expression: Option<String>,
-    pub description: Option<String>,
-    pub start_at: Option<String>,
-    pub end_at: Option<String>,
-}
+    pub fn add_checklist_item(&mut self, text: &str) -> AppResult<()> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(AppError::Validation(
+                "Checklist item cannot be empty".to_string(),
+            ));
+        }
+        self.checklist.push(ChecklistItem {
+            id: Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            done: false,
+        });
+        Ok(())
+    }
+
+    pub fn toggle_checklist_item(&mut self, item_id: &str) -> AppResult<()> {
+        let item = self
+            .checklist
+            .iter_mut()
+            .find(|item| item.id == item_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Checklist item with id {} not found", item_id))
+            })?;
+        item.done = !item.done;
+        Ok(())
+    }
+
+    pub fn remove_checklist_item(&mut self, item_id: &str) -> AppResult<()> {
+        let initial_len = self.checklist.len();
+        self.checklist.retain(|item| item.id != item_id);
+        if self.checklist.len() == initial_len {
+            return Err(AppError::NotFound(format!(
+                "Checklist item with id {} not found",
+                item_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// 按给定的 ID 顺序重排清单，必须恰好包含全部清单项
+    pub fn reorder_checklist(&mut self, item_ids: &[String]) -> AppResult<()> {
+        let is_permutation = item_ids.len() == self.checklist.len()
+            && self
+                .checklist
+                .iter()
+                .all(|item| item_ids.iter().filter(|id| **id == item.id).count() == 1);
+        if !is_permutation {
+            return Err(AppError::Validation(
+                "Reorder must list every checklist item exactly once".to_string(),
+            ));
+        }
+        self.checklist.sort_by_key(|item| item_ids.iter().position(|id| *id == item.id));
+        Ok(())
+    }
+
+    /// 新的一次触发开始：重复提醒重置清单
+    pub fn begin_occurrence(&mut self) {
+        if self.schedule.is_recurring() {
+            for item in &mut self.checklist {
+                item.done = false;
+            }
+        }
+    }
+
+    /// 在通知正文后附上清单剩余项数和附件数
+    pub fn notification_body(&self, body: &str) -> String {
+        let mut body = body.to_string();
+        if !self.checklist.is_empty() {
+            let remaining = self.checklist.iter().filter(|item| !item.done).count();
+            body.push_str(&format!("（清单还剩 {}/{} 项）", remaining, self.checklist.len()));
+        }
+        if !self.attachments.is_empty() {
+            body.push_str(&format!("（{} 个附件）", self.attachments.len()));
+        }
+        body
+    }
+
+    pub fn find_attachment(&self, attachment_id: &str) -> AppResult<&Attachment> {
+        self.attachments
+            .iter()
+            .find(|attachment| attachment.id == attachment_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Attachment with id {} not found", attachment_id))
+            })
+    }
+
+    pub fn remove_attachment(&mut self, attachment_id: &str) -> AppResult<Attachment> {
+        let index = self
+            .attachments
+            .iter()
+            .position(|attachment| attachment.id == attachment_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Attachment with id {} not found", attachment_id))
+            })?;
+        Ok(self.attachments.remove(index))
+    }
+
+    /// 提醒是否属于当前情境：未设置当前情境，或提醒（及其分组）未限定情境时总是属于
+    pub fn matches_context(&self, group: Option<&ReminderGroup>, active: Option<&str>) -> bool {
+        let Some(active) = active else {
+            return true;
+        };
+        let contexts = if self.contexts.is_empty() {
+            group.map(|group| group.contexts.as_slice()).unwrap_or_default()
+        } else {
+            self.contexts.as_slice()
+        };
+        contexts.is_empty() || contexts.iter().any(|context| context == active)
+    }
+
+    /// 设置每日通知上限，0 表示取消限制
+    pub fn set_max_per_day(&mut self, max_per_day: u32) {
+        self.max_per_day = Some(max_per_day).filter(|max| *max > 0);
+    }
+
+    /// 设置调度计划：截止提醒重新计算各阶段，按完成间隔重复的提醒从现在开始计时
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.next_due_at = match schedule {
+            Schedule::AfterCompletion { interval_days } => {
+                Some(chrono::Utc::now().timestamp() + i64::from(interval_days) * 86400)
+            }
+            _ => None,
+        };
+        self.sent_deadline_alerts.clear();
+        self.schedule = schedule;
+    }
+
+    /// 按分钟检查的计划（间隔、截止提醒）在 now 时是否需要通知，其他计划由任务时间决定
+    pub fn is_due(&self, now: i64) -> bool {
+        match &self.schedule {
+            Schedule::Interval { minutes } => {
+                let since = self.last_triggered.unwrap_or(self.created_at);
+                now - since >= i64::from(*minutes) * 60
+            }
+            Schedule::Deadline { .. } => self.due_deadline_alert(now).is_some(),
+            Schedule::Cron { .. } | Schedule::Once { .. } | Schedule::AfterCompletion { .. } => {
+                true
+            }
+            Schedule::Unscheduled => false,
+        }
+    }
+
+    /// 单次提醒触发（或在假期中错过）后即完成
+    pub fn finish_if_once(&mut self) {
+        if matches!(self.schedule, Schedule::Once { .. }) && self.is_active() {
+            if let Err(e) = self.complete() {
+                eprintln!("Failed to complete one-shot reminder {}: {}", self.id, e);
+            }
+        }
+    }
+
+    /// 记录一次完成：单次和截止提醒进入完成状态，重复提醒只记录本次完成，
+    /// 按完成间隔重复的提醒重新计时
+    pub fn record_completion(&mut self) -> AppResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        match self.schedule {
+            Schedule::Once { .. } | Schedule::Deadline { .. } | Schedule::Unscheduled => {
+                self.complete()?
+            }
+            Schedule::Cron { .. }
+            | Schedule::Interval { .. }
+            | Schedule::AfterCompletion { .. } => {
+                if !self.is_active() {
+                    return Err(AppError::Validation(format!(
+                        "Cannot complete reminder {} while it is {}",
+                        self.id, self.status
+                    )));
+                }
+                if let Schedule::AfterCompletion { interval_days } = self.schedule {
+                    self.next_due_at = Some(now + i64::from(interval_days) * 86400);
+                }
+            }
+        }
+        self.last_completed_at = Some(now);
+        self.acknowledge();
+        Ok(())
+    }
+
+    /// 计算截止提醒在 now 时应发出的通知：优先最近一个未发出的阶段，其次逾期催促
+    pub fn due_deadline_alert(&self, now: i64) -> Option<DeadlineAlert> {
+        let Schedule::Deadline {
+            due_at,
+            alert_minutes_before,
+            overdue_repeat_minutes,
+        } = &self.schedule
+        else {
+            return None;
+        };
+
+        let pending_stage = alert_minutes_before
+            .iter()
+            .filter(|minutes| !self.sent_deadline_alerts.contains(minutes))
+            .filter(|minutes| due_at - i64::from(**minutes) * 60 <= now)
+            .min();
+        if let Some(minutes) = pending_stage {
+            return Some(DeadlineAlert::Stage(*minutes));
+        }
+
+        if now <= *due_at || *overdue_repeat_minutes == 0 {
+            return None;
+        }
+        let repeat_due = self
+            .last_triggered
+            .is_none_or(|last| now - last >= i64::from(*overdue_repeat_minutes) * 60);
+        repeat_due.then_some(DeadlineAlert::Overdue)
+    }
+
+    /// 记录已发出的截止提醒；错过的更早阶段一并标记，避免补发
+    pub fn mark_deadline_alert(&mut self, now: i64) {
+        if let Schedule::Deadline {
+            due_at,
+            alert_minutes_before,
+            ..
+        } = &self.schedule
+        {
+            for minutes in alert_minutes_before {
+                if due_at - i64::from(*minutes) * 60 <= now
+                    && !self.sent_deadline_alerts.contains(minutes)
+                {
+                    self.sent_deadline_alerts.push(*minutes);
+                }
+            }
+        }
+    }
+
+    /// 截止提醒通知正文，附带剩余或逾期时间
+    pub fn deadline_message(&self, now: i64) -> String {
+        let Schedule::Deadline { due_at, .. } = &self.schedule else {
+            return self.title.clone();
+        };
+
+        let remaining = due_at - now;
+        if remaining > 0 {
+            format!("{}（还剩{}）", self.title, format_duration(remaining))
+        } else if remaining > -60 {
+            format!("{}（已到截止时间）", self.title)
+        } else {
+            format!("{}（已逾期{}）", self.title, format_duration(remaining))
+        }
+    }
+
+    /// 更新提醒，调度计划和时间窗口使用 validation 解析后的结果
+    pub fn update(
+        &mut self,
+        request: UpdateReminderRequest,
+        parsed: ParsedSchedule,
+    ) -> AppResult<bool> {
+        let mut schedule_changed = false;
+
+        if let Some(title) = request.title {
+            self.title = title;
+        }
+        if let Some(color) = request.color {
+            self.color = color;
+        }
+        if let Some(schedule) = parsed.schedule {
+            self.set_schedule(schedule);
+            schedule_changed = true;
+        }
+        if let Some(description) = request.description {
+            self.description = Some(description);
+        }
+        if let Some(windows) = parsed.windows {
+            self.set_windows(windows)?;
+            schedule_changed = true;
+        }
+        if let Some(max_per_day) = request.max_per_day {
+            self.set_max_per_day(max_per_day);
+        }
+        if let Some(contexts) = request.contexts {
+            self.contexts = contexts;
+        }
+        if let Some(priority) = request.priority {
+            self.priority = priority;
+            if !priority.requires_acknowledgement() {
+                self.acknowledge();
+            }
+        }
+        if let Some(tags) = request.tags {
+            self.tags = tags;
+        }
+
+        Ok(schedule_changed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReminderRequest {
+    pub title: String,
+    pub color: String,
+    pub group_id: String,
+    pub schedule: ScheduleInput,
+    pub description: Option<String>,
+    /// 初始清单项
+    pub checklist: Option<Vec<String>>,
+    /// 按星期设置的时间窗口
+    pub windows: Option<Vec<TimeWindowInput>>,
+    /// 每天最多通知次数
+    pub max_per_day: Option<u32>,
+    /// 所属情境 ID
+    pub contexts: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    /// 标签 ID
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateReminderRequest {
+    pub title: Option<String>,
+    pub color: Option<String>,
+    pub schedule: Option<ScheduleInput>,
+    pub description: Option<String>,
+    /// 传空列表表示清除时间窗口
+    pub windows: Option<Vec<TimeWindowInput>>,
+    /// 传 0 表示取消每日上限
+    pub max_per_day: Option<u32>,
+    /// 传空列表表示改为沿用分组的情境
+    pub contexts: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    /// 标签 ID
+    pub tags: Option<Vec<String>>,
+}
+
+/// validation 从请求中解析出的调度计划和时间窗口，未修改的为空
+#[derive(Debug, Clone, Default)]
+pub struct ParsedSchedule {
+    pub schedule: Option<Schedule>,
+    pub windows: Option<Vec<TimeWindow>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseReminderRequest {
+    /// 暂停到指定时间，格式为 `YYYY-MM-DD HH:MM`（本地时间）或 RFC 3339
+    pub until: Option<String>,
+    /// 暂停指定分钟数
+    pub duration_minutes: Option<i64>,
+    pub notify_on_resume: Option<bool>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateGroupRequest {
     pub name: String,
     pub color: String,
+    pub contexts: Option<Vec<String>>,
+}
+
+/// 调度任务跳过通知的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    NotFound,
+    NotActive,
+    NotDue,
+    Overridden,
+    BeforeStart,
+    AfterEnd,
+    /// 位于两个窗口之间，或当天没有窗口
+    OutsideWindow,
+    /// 当天通知次数已达上限
+    DailyCapReached,
+    /// 不属于当前情境
+    InactiveContext,
+    /// 处于假期模式
+    Vacation,
+    /// 处于免打扰时段
+    QuietHours,
+}
+
+/// 调度任务单次执行的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobRunResult {
+    Notified,
+    Skipped { reason: SkipReason },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRunRecord {
+    pub ran_at: i64,
+    pub result: JobRunResult,
+}
+
+/// 用户对一次触发的响应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OccurrenceResponse {
+    Acknowledged,
+    Completed,
+    Missed,
+}
+
+/// 触发记录：每次计划触发追加一条，之后只会补记用户响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OccurrenceLogEntry {
+    pub id: String,
+    pub reminder_id: String,
+    /// 计划触发时间
+    pub scheduled_at: i64,
+    /// 实际执行时间
+    pub fired_at: i64,
+    pub result: JobRunResult,
+    #[serde(default)]
+    pub response: Option<OccurrenceResponse>,
+    #[serde(default)]
+    pub responded_at: Option<i64>,
+}
+
+impl OccurrenceLogEntry {
+    pub fn new(reminder_id: &str, scheduled_at: i64, result: JobRunResult) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            reminder_id: reminder_id.to_string(),
+            scheduled_at,
+            fired_at: chrono::Utc::now().timestamp(),
+            result,
+            response: None,
+            responded_at: None,
+        }
+    }
+
+    pub fn is_delivered(&self) -> bool {
+        matches!(self.result, JobRunResult::Notified)
+    }
+
+    /// 记录用户响应：已有完成或错过的标记时只接受手动修改，确认不会覆盖它们
+    pub fn respond(&mut self, response: OccurrenceResponse, at: i64) -> bool {
+        let replaceable = match self.response {
+            None => true,
+            Some(OccurrenceResponse::Acknowledged) => response != OccurrenceResponse::Acknowledged,
+            Some(_) => false,
+        };
+        if replaceable {
+            self.response = Some(response);
+            self.responded_at = Some(at);
+        }
+        replaceable
+    }
+
+    /// 是否在 [from, to] 范围内（按计划触发时间）
+    pub fn in_range(&self, from: Option<i64>, to: Option<i64>) -> bool {
+        from.is_none_or(|from| self.scheduled_at >= from)
+            && to.is_none_or(|to| self.scheduled_at <= to)
+    }
+}
+
+/// 完成情况统计，按已送达的触发计算；连续天数指连续完成的触发次数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdherenceStats {
+    /// 计入统计的触发数，不含最近一次尚未响应的触发
+    pub tracked: u32,
+    pub completed: u32,
+    pub current_streak: u32,
+    /// 保留的触发记录范围内最长的连续完成次数
+    pub best_streak: u32,
+    /// 最近 7 天的完成率（0-1），没有触发时为空
+    pub completion_rate_7d: Option<f64>,
+    pub completion_rate_30d: Option<f64>,
+}
+
+impl AdherenceStats {
+    /// 单个提醒的完成结果 (计划触发时间, 是否完成)：只看已送达的触发，
+    /// 确认但未完成视为错过；最近一次未响应的触发仍在等待，不计入
+    pub fn outcomes(entries: &[&OccurrenceLogEntry]) -> Vec<(i64, bool)> {
+        let mut delivered: Vec<&OccurrenceLogEntry> =
+            entries.iter().copied().filter(|entry| entry.is_delivered()).collect();
+        delivered.sort_by_key(|entry| entry.scheduled_at);
+        if delivered.last().is_some_and(|entry| entry.response.is_none()) {
+            delivered.pop();
+        }
+        delivered
+            .into_iter()
+            .map(|entry| {
+                let done = entry.response == Some(OccurrenceResponse::Completed);
+                (entry.scheduled_at, done)
+            })
+            .collect()
+    }
+
+    /// 由按时间排序的完成结果计算统计
+    pub fn from_outcomes(outcomes: &[(i64, bool)], now: i64) -> Self {
+        let rate = |days: i64| {
+            let since = now - days * 86400;
+            let recent: Vec<bool> = outcomes
+                .iter()
+                .filter(|(at, _)| *at >= since)
+                .map(|(_, done)| *done)
+                .collect();
+            if recent.is_empty() {
+                return None;
+            }
+            let completed = recent.iter().filter(|done| **done).count();
+            Some(completed as f64 / recent.len() as f64)
+        };
+
+        let mut best_streak = 0;
+        let mut streak = 0;
+        for (_, done) in outcomes {
+            streak = if *done { streak + 1 } else { 0 };
+            best_streak = best_streak.max(streak);
+        }
+
+        Self {
+            tracked: outcomes.len() as u32,
+            completed: outcomes.iter().filter(|(_, done)| *done).count() as u32,
+            current_streak: streak,
+            best_streak,
+            completion_rate_7d: rate(7),
+            completion_rate_30d: rate(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderStats {
+    pub reminder_id: String,
+    pub title: String,
+    pub group_id: String,
+    #[serde(flatten)]
+    pub stats: AdherenceStats,
+}
+
+/// 分组统计：合并组内所有提醒的触发后按时间计算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupStats {
+    pub group_id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: AdherenceStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsReport {
+    pub generated_at: i64,
+    pub reminders: Vec<ReminderStats>,
+    pub groups: Vec<GroupStats>,
+}
+
+/// 触发记录的保留策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryRetention {
+    /// 超过天数的记录会被清理
+    pub max_age_days: u32,
+    /// 每个提醒最多保留的记录数
+    pub max_entries_per_reminder: u32,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: 90,
+            max_entries_per_reminder: 500,
+        }
+    }
+}
+
+impl HistoryRetention {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.max_age_days == 0 || self.max_entries_per_reminder == 0 {
+            return Err(AppError::Validation(
+                "History retention limits must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 按保留策略清理记录（记录按追加顺序排列），返回清理的条数
+    pub fn apply(&self, log: &mut Vec<OccurrenceLogEntry>, now: i64) -> usize {
+        let initial_len = log.len();
+        let cutoff = now - i64::from(self.max_age_days) * 86400;
+        log.retain(|entry| entry.fired_at >= cutoff);
+
+        // 从最新的记录往前数，超出每个提醒上限的丢弃
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let mut keep: Vec<bool> = log
+            .iter()
+            .rev()
+            .map(|entry| {
+                let count = counts.entry(entry.reminder_id.clone()).or_default();
+                *count += 1;
+                *count <= self.max_entries_per_reminder
+            })
+            .collect();
+        keep.reverse();
+        let mut keep = keep.into_iter();
+        log.retain(|_| keep.next().unwrap_or(true));
+
+        initial_len - log.len()
+    }
+}
+
+/// 调度器中登记的单个任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJobInfo {
+    pub job_id: String,
+    pub reminder_id: String,
+    /// 是否记录在 job_ids 中，为 false 表示任务已脱离跟踪
+    pub tracked: bool,
+    pub next_fire_at: Option<i64>,
+    pub last_run: Option<JobRunRecord>,
+}
+
+/// 调度器与仓库之间的不一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchedulerIssue {
+    /// 活跃提醒没有对应的任务
+    MissingJob { reminder_id: String },
+    /// 任务对应的提醒已不存在或不再活跃
+    StaleJob { reminder_id: String, job_id: String },
+    /// 同一个提醒登记了多个任务
+    DuplicateJobs { reminder_id: String, job_ids: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerDiagnostics {
+    pub jobs: Vec<ScheduledJobInfo>,
+    pub issues: Vec<SchedulerIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    pub repaired: Vec<SchedulerIssue>,
+    pub diagnostics: SchedulerDiagnostics,
+}
+
+/// 日历视图中的一次具体触发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Occurrence {
+    pub reminder_id: String,
+    pub title: String,
+    pub color: String,
+    pub at: i64,
+    /// 是否为改期后的触发
+    pub moved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OccurrenceList {
+    pub occurrences: Vec<Occurrence>,
+    /// 结果数量达到上限被截断
+    pub truncated: bool,
+}
+
+/// 创建或更新提醒时发现的调度冲突，仅作提示，不阻止保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleWarning {
+    /// 同一分钟内触发的提醒过多
+    CrowdedMinute { at: i64, reminder_ids: Vec<String> },
+    /// 与同一分组内其他提醒的时间窗口重叠
+    OverlappingWindow { reminder_id: String },
+    /// 全局每小时触发次数超过阈值
+    HighFireRate { hour_start: i64, fires: usize, limit: usize },
+}
+
+/// 保存后的提醒及其调度冲突提示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedReminder {
+    #[serde(flatten)]
+    pub reminder: Reminder,
+    pub warnings: Vec<ScheduleWarning>,
+}
+
+/// 复制的分组及其提醒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatedGroup {
+    pub group: ReminderGroup,
+    pub reminders: Vec<Reminder>,
+}
+
+/// 例程步骤触发后，下一步从何时开始计时
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAdvance {
+    /// 本步骤触发后立即开始计时
+    #[default]
+    Fired,
+    /// 本步骤被确认后才开始计时
+    Acknowledged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineStep {
+    pub id: String,
+    pub title: String,
+    /// 距上一步（第一步为例程开始）多少分钟后触发
+    pub offset_minutes: u32,
+    #[serde(default)]
+    pub advance_on: StepAdvance,
+}
+
+/// 正在进行的一次例程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineRun {
+    pub started_at: i64,
+    pub current_step: usize,
+    /// 当前步骤的触发时间，为空表示已触发、等待确认
+    pub next_step_at: Option<i64>,
+}
+
+/// 按顺序依次提醒的一组步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Routine {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub steps: Vec<RoutineStep>,
+    /// 自动开始例程的 cron 表达式，为空时只能手动开始
+    pub cron_expression: Option<String>,
+    pub is_enabled: bool,
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    pub last_started_at: Option<i64>,
+    pub run: Option<RoutineRun>,
+}
+
+impl Routine {
+    pub fn new(request: CreateRoutineRequest) -> AppResult<Self> {
+        let now = chrono::Utc::now().timestamp();
+        let mut routine = Self {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            color: request.color,
+            steps: Vec::new(),
+            cron_expression: request.cron_expression.filter(|expr| !expr.trim().is_empty()),
+            is_enabled: true,
+            created_at: now,
+            updated_at: now,
+            last_started_at: None,
+            run: None,
+        };
+        routine.set_steps(request.steps)?;
+        Ok(routine)
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now().timestamp();
+    }
+
+    pub fn update(&mut self, request: UpdateRoutineRequest) -> AppResult<()> {
+        if let Some(name) = request.name {
+            self.name = name;
+        }
+        if let Some(color) = request.color {
+            self.color = color;
+        }
+        if let Some(steps) = request.steps {
+            self.set_steps(steps)?;
+            // 步骤变化后原进度已无意义
+            self.run = None;
+        }
+        if let Some(cron_expression) = request.cron_expression {
+            self.cron_expression = Some(cron_expression).filter(|expr| !expr.trim().is_empty());
+        }
+        if let Some(is_enabled) = request.is_enabled {
+            self.is_enabled = is_enabled;
+        }
+        Ok(())
+    }
+
+    fn set_steps(&mut self, steps: Vec<RoutineStepRequest>) -> AppResult<()> {
+        if steps.is_empty() {
+            return Err(AppError::Validation(
+                "Routine must have at least one step".to_string(),
+            ));
+        }
+        if steps.iter().any(|step| step.title.trim().is_empty()) {
+            return Err(AppError::Validation(
+                "Routine step title cannot be empty".to_string(),
+            ));
+        }
+        self.steps = steps
+            .into_iter()
+            .map(|step| RoutineStep {
+                id: Uuid::new_v4().to_string(),
+                title: step.title,
+                offset_minutes: step.offset_minutes,
+                advance_on: step.advance_on.unwrap_or_default(),
+            })
+            .collect();
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// 从第一步开始一次新的例程
+    pub fn start(&mut self, now: i64) -> AppResult<()> {
+        if self.is_running() {
+            return Err(AppError::Validation(format!(
+                "Routine {} is already running",
+                self.id
+            )));
+        }
+        let first = self.steps.first().ok_or_else(|| {
+            AppError::Validation("Routine must have at least one step".to_string())
+        })?;
+        self.run = Some(RoutineRun {
+            started_at: now,
+            current_step: 0,
+            next_step_at: Some(now + i64::from(first.offset_minutes) * 60),
+        });
+        self.last_started_at = Some(now);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.run = None;
+    }
+
+    /// now 时已到触发时间的步骤及其序号
+    pub fn due_step(&self, now: i64) -> Option<(usize, &RoutineStep)> {
+        let run = self.run.as_ref()?;
+        if run.next_step_at? > now {
+            return None;
+        }
+        self.steps.get(run.current_step).map(|step| (run.current_step, step))
+    }
+
+    /// 当前步骤已触发：需要确认的步骤进入等待，否则开始下一步计时
+    pub fn mark_step_fired(&mut self, now: i64) {
+        let Some(run) = self.run.as_mut() else {
+            return;
+        };
+        match self.steps.get(run.current_step).map(|step| step.advance_on) {
+            Some(StepAdvance::Acknowledged) => run.next_step_at = None,
+            _ => self.advance(now),
+        }
+    }
+
+    /// 确认当前步骤，开始下一步计时
+    pub fn acknowledge_step(&mut self, now: i64) -> AppResult<()> {
+        let awaiting = self
+            .run
+            .as_ref()
+            .is_some_and(|run| run.next_step_at.is_none());
+        if !awaiting {
+            return Err(AppError::Validation(format!(
+                "Routine {} has no step awaiting acknowledgement",
+                self.id
+            )));
+        }
+        self.advance(now);
+        Ok(())
+    }
+
+    /// 进入下一步，最后一步结束后例程完成
+    fn advance(&mut self, from: i64) {
+        let Some(run) = self.run.as_mut() else {
+            return;
+        };
+        run.current_step += 1;
+        match self.steps.get(run.current_step) {
+            Some(step) => run.next_step_at = Some(from + i64::from(step.offset_minutes) * 60),
+            None => self.run = None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineStepRequest {
+    pub title: String,
+    pub offset_minutes: u32,
+    pub advance_on: Option<StepAdvance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoutineRequest {
+    pub name: String,
+    pub color: String,
+    pub steps: Vec<RoutineStepRequest>,
+    pub cron_expression: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRoutineRequest {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    /// 修改步骤会结束正在进行的例程
+    pub steps: Option<Vec<RoutineStepRequest>>,
+    /// 传空字符串表示取消自动开始
+    pub cron_expression: Option<String>,
+    pub is_enabled: Option<bool>,
+}
+
+/// 提醒列表的排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderSort {
+    #[default]
+    Priority,
+    Title,
+    LastTriggered,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// 提醒列表查询条件，未设置的条件不过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReminderQuery {
+    pub group_id: Option<String>,
+    pub statuses: Option<Vec<ReminderStatus>>,
+    pub priorities: Option<Vec<Priority>>,
+    pub min_priority: Option<Priority>,
+    /// 带有其中任一标签
+    pub tag_ids: Option<Vec<String>>,
+    pub sort_by: Option<ReminderSort>,
+    /// 默认按优先级从高到低，其他字段从小到大
+    pub descending: Option<bool>,
+}
+
+impl ReminderQuery {
+    pub fn matches(&self, reminder: &Reminder) -> bool {
+        self.group_id.as_ref().is_none_or(|id| &reminder.group_id == id)
+            && self
+                .statuses
+                .as_ref()
+                .is_none_or(|statuses| statuses.contains(&reminder.status))
+            && self
+                .priorities
+                .as_ref()
+                .is_none_or(|priorities| priorities.contains(&reminder.priority))
+            && self.min_priority.is_none_or(|min| reminder.priority >= min)
+            && self
+                .tag_ids
+                .as_ref()
+                .is_none_or(|tag_ids| reminder.tags.iter().any(|tag| tag_ids.contains(tag)))
+    }
+
+    /// 按排序字段和方向排序，字段相同时总是按标题升序
+    pub fn sort(&self, reminders: &mut [Reminder]) {
+        let sort_by = self.sort_by.unwrap_or_default();
+        let descending = self
+            .descending
+            .unwrap_or(sort_by == ReminderSort::Priority);
+        reminders.sort_by(|a, b| {
+            let order = match sort_by {
+                ReminderSort::Priority => a.priority.cmp(&b.priority),
+                ReminderSort::Title => a.title.cmp(&b.title),
+                ReminderSort::LastTriggered => a.last_triggered.cmp(&b.last_triggered),
+                ReminderSort::CreatedAt => a.created_at.cmp(&b.created_at),
+                ReminderSort::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            };
+            let order = if descending { order.reverse() } else { order };
+            order.then_with(|| a.title.cmp(&b.title))
+        });
+    }
+}
+
+/// 对带有某个标签的所有提醒执行的批量操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TagAction {
+    Pause {
+        until: Option<String>,
+        duration_minutes: Option<i64>,
+    },
+    Resume,
+    Cancel,
+    Complete,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFailure {
+    pub reminder_id: String,
+    pub error: String,
+}
+
+/// 批量操作结果，单个提醒失败不影响其他提醒
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkResult {
+    pub updated: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DAY: i64 = 86400;
+    const NOW: i64 = 100 * DAY;
+
+    fn entry(reminder_id: &str, scheduled_at: i64) -> OccurrenceLogEntry {
+        let mut entry = OccurrenceLogEntry::new(reminder_id, scheduled_at, JobRunResult::Notified);
+        entry.fired_at = scheduled_at;
+        entry
+    }
+
+    fn times(log: &[OccurrenceLogEntry]) -> Vec<(&str, i64)> {
+        log.iter().map(|entry| (entry.reminder_id.as_str(), entry.fired_at)).collect()
+    }
+
+    #[test]
+    fn retention_drops_entries_older_than_max_age() {
+        let retention = HistoryRetention { max_age_days: 30, max_entries_per_reminder: 500 };
+        let mut log = vec![
+            entry("a", NOW - 31 * DAY),
+            entry("b", NOW - 30 * DAY),
+            entry("a", NOW - DAY),
+        ];
+
+        assert_eq!(retention.apply(&mut log, NOW), 1);
+        assert_eq!(times(&log), vec![("b", NOW - 30 * DAY), ("a", NOW - DAY)]);
+    }
+
+    #[test]
+    fn retention_keeps_newest_entries_per_reminder() {
+        let retention = HistoryRetention { max_age_days: 90, max_entries_per_reminder: 2 };
+        let mut log = vec![
+            entry("a", NOW - 4 * DAY),
+            entry("b", NOW - 4 * DAY),
+            entry("a", NOW - 3 * DAY),
+            entry("a", NOW - 2 * DAY),
+            entry("a", NOW - DAY),
+        ];
+
+        assert_eq!(retention.apply(&mut log, NOW), 2);
+        assert_eq!(
+            times(&log),
+            vec![("b", NOW - 4 * DAY), ("a", NOW - 2 * DAY), ("a", NOW - DAY)]
+        );
+        assert_eq!(retention.apply(&mut log, NOW), 0);
+    }
+
+    fn answered(scheduled_at: i64, response: Option<OccurrenceResponse>) -> OccurrenceLogEntry {
+        let mut entry = entry("a", scheduled_at);
+        if let Some(response) = response {
+            entry.respond(response, scheduled_at + 60);
+        }
+        entry
+    }
+
+    #[test]
+    fn only_acknowledgement_can_be_replaced_by_a_later_response() {
+        let mut acknowledged = answered(NOW, Some(OccurrenceResponse::Acknowledged));
+        assert!(!acknowledged.respond(OccurrenceResponse::Acknowledged, NOW + 120));
+        assert_eq!(acknowledged.responded_at, Some(NOW + 60));
+        assert!(acknowledged.respond(OccurrenceResponse::Completed, NOW + 120));
+        assert_eq!(acknowledged.response, Some(OccurrenceResponse::Completed));
+        assert_eq!(acknowledged.responded_at, Some(NOW + 120));
+
+        for response in [OccurrenceResponse::Completed, OccurrenceResponse::Missed] {
+            let mut entry = answered(NOW, Some(response));
+            assert!(!entry.respond(OccurrenceResponse::Acknowledged, NOW + 120));
+            assert!(!entry.respond(OccurrenceResponse::Missed, NOW + 120));
+            assert_eq!(entry.response, Some(response));
+        }
+    }
+
+    #[test]
+    fn outcomes_ignore_skipped_and_pending_occurrences() {
+        let mut skipped = entry("a", NOW - 3 * DAY);
+        skipped.result = JobRunResult::Skipped { reason: SkipReason::OutsideWindow };
+        let log = [
+            answered(NOW - 4 * DAY, Some(OccurrenceResponse::Completed)),
+            skipped,
+            answered(NOW - 2 * DAY, Some(OccurrenceResponse::Acknowledged)),
+            answered(NOW - DAY, None),
+            answered(NOW, None),
+        ];
+        let entries: Vec<&OccurrenceLogEntry> = log.iter().collect();
+
+        assert_eq!(
+            AdherenceStats::outcomes(&entries),
+            vec![(NOW - 4 * DAY, true), (NOW - 2 * DAY, false), (NOW - DAY, false)]
+        );
+    }
+
+    #[test]
+    fn streaks_reset_on_missed_occurrences() {
+        let log = [
+            answered(NOW - 6 * DAY, Some(OccurrenceResponse::Completed)),
+            answered(NOW - 5 * DAY, Some(OccurrenceResponse::Completed)),
+            answered(NOW - 4 * DAY, Some(OccurrenceResponse::Completed)),
+            answered(NOW - 3 * DAY, Some(OccurrenceResponse::Missed)),
+            answered(NOW - 2 * DAY, Some(OccurrenceResponse::Completed)),
+            answered(NOW - DAY, Some(OccurrenceResponse::Completed)),
+        ];
+        let entries: Vec<&OccurrenceLogEntry> = log.iter().collect();
+        let stats = AdherenceStats::from_outcomes(&AdherenceStats::outcomes(&entries), NOW);
+
+        assert_eq!(stats.tracked, 6);
+        assert_eq!(stats.completed, 5);
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.best_streak, 3);
+    }
+
+    #[test]
+    fn completion_rates_only_count_recent_outcomes() {
+        let outcomes = [
+            (NOW - 20 * DAY, false),
+            (NOW - 10 * DAY, false),
+            (NOW - 5 * DAY, true),
+            (NOW - DAY, false),
+        ];
+        let stats = AdherenceStats::from_outcomes(&outcomes, NOW);
+
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.best_streak, 1);
+        assert_eq!(stats.completion_rate_7d, Some(0.5));
+        assert_eq!(stats.completion_rate_30d, Some(0.25));
+        assert_eq!(AdherenceStats::from_outcomes(&[], NOW), AdherenceStats::default());
+    }
+
+    fn reminder() -> Reminder {
+        let schedule = Schedule::Cron { expression: "0 0 9 * * *".to_string() };
+        let (title, color) = ("喝水".to_string(), "#007AFF".to_string());
+        Reminder::new(title, color, "default".to_string(), schedule, None)
+    }
+
+    #[test]
+    fn pausing_again_updates_resume_time() {
+        let mut reminder = reminder();
+        reminder.pause(Some(NOW), false).unwrap();
+
+        reminder.pause(Some(NOW + DAY), true).unwrap();
+        assert_eq!(reminder.status, ReminderStatus::Paused);
+        assert_eq!(reminder.paused_until, Some(NOW + DAY));
+        assert!(reminder.notify_on_resume);
+
+        reminder.pause(None, false).unwrap();
+        assert_eq!(reminder.paused_until, None);
+    }
+
+    #[test]
+    fn completing_a_cron_reminder_keeps_it_active() {
+        let mut reminder = reminder();
+
+        reminder.record_completion().unwrap();
+        reminder.record_completion().unwrap();
+        assert_eq!(reminder.status, ReminderStatus::Active);
+        assert!(reminder.last_completed_at.is_some());
+    }
+
+    #[test]
+    fn completing_a_one_time_reminder_ends_it() {
+        let mut reminder = reminder();
+        reminder.schedule = Schedule::Once { at: NOW };
+
+        reminder.record_completion().unwrap();
+        assert_eq!(reminder.status, ReminderStatus::Completed);
+        assert!(reminder.record_completion().is_err());
+    }
+
+    #[test]
+    fn completing_an_after_completion_reminder_restarts_the_interval() {
+        let mut reminder = reminder();
+        let before = chrono::Utc::now().timestamp();
+        reminder.set_schedule(Schedule::AfterCompletion { interval_days: 3 });
+        let first_due = reminder.next_due_at.unwrap();
+        assert!(first_due >= before + 3 * DAY);
+
+        reminder.next_due_at = Some(before - DAY);
+        reminder.record_completion().unwrap();
+        let after = chrono::Utc::now().timestamp();
+        let next_due = reminder.next_due_at.unwrap();
+        assert!((before + 3 * DAY..=after + 3 * DAY).contains(&next_due));
+        assert_eq!(reminder.status, ReminderStatus::Active);
+        assert!(reminder.last_completed_at.is_some());
+    }
+
+    #[test]
+    fn paused_cron_reminder_cannot_be_completed() {
+        let mut reminder = reminder();
+        reminder.pause(None, false).unwrap();
+
+        assert!(reminder.record_completion().is_err());
+        assert_eq!(reminder.last_completed_at, None);
+    }
+
+    #[test]
+    fn stored_schedule_is_validated() {
+        let invalid = [
+            json!({ "type": "cron", "expression": "" }),
+            json!({ "type": "cron", "expression": "not a schedule" }),
+            json!({ "type": "interval", "minutes": 0 }),
+            json!({ "type": "after_completion", "interval_days": 0 }),
+            json!({ "type": "once", "at": -1 }),
+        ];
+        for schedule in invalid {
+            assert!(serde_json::from_value::<Schedule>(schedule.clone()).is_err(), "{}", schedule);
+        }
+
+        let unscheduled = serde_json::to_value(Schedule::Unscheduled).unwrap();
+        assert_eq!(unscheduled, json!({ "type": "unscheduled" }));
+        assert_eq!(serde_json::from_value::<Schedule>(unscheduled).unwrap(), Schedule::Unscheduled);
+    }
+
+    #[test]
+    fn schedule_and_window_round_trip_through_json() {
+        let schedules = [
+            Schedule::Cron { expression: "0 0 9 * * *".to_string() },
+            Schedule::Once { at: NOW },
+            Schedule::Interval { minutes: 30 },
+            Schedule::AfterCompletion { interval_days: 2 },
+            Schedule::deadline(NOW, Some(vec![60, 10]), Some(15)),
+        ];
+        for schedule in schedules {
+            let stored = serde_json::to_value(&schedule).unwrap();
+            assert_eq!(serde_json::from_value::<Schedule>(stored).unwrap(), schedule);
+        }
+
+        let deadline = json!({ "type": "deadline", "due_at": NOW });
+        assert_eq!(
+            serde_json::from_value::<Schedule>(deadline).unwrap(),
+            Schedule::deadline(NOW, None, None)
+        );
+
+        let window = json!({ "weekdays": [1, 5], "start": "08:30", "end": "18:00" });
+        let parsed: TimeWindow = serde_json::from_value(window.clone()).unwrap();
+        assert_eq!(parsed.start, NaiveTime::from_hms_opt(8, 30, 0).unwrap());
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), window);
+    }
+
+    #[test]
+    fn window_skip_reason_tells_where_the_time_falls() {
+        let at = |hour, minute| {
+            // 2024-01-01 为周一
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+        };
+        let window = |weekdays: Vec<u32>, start: &str, end: &str| TimeWindow {
+            weekdays,
+            start: parse_hour_minute(start).unwrap(),
+            end: parse_hour_minute(end).unwrap(),
+        };
+
+        let mut reminder = reminder();
+        assert_eq!(reminder.window_skip_reason(at(3, 0)), None);
+
+        reminder.windows = vec![window(vec![], "09:00", "12:00"), window(vec![], "14:00", "18:00")];
+        assert_eq!(reminder.window_skip_reason(at(9, 0)), None);
+        assert_eq!(reminder.window_skip_reason(at(18, 0)), None);
+        assert_eq!(reminder.window_skip_reason(at(8, 59)), Some(SkipReason::BeforeStart));
+        assert_eq!(reminder.window_skip_reason(at(13, 0)), Some(SkipReason::OutsideWindow));
+        assert_eq!(reminder.window_skip_reason(at(18, 1)), Some(SkipReason::AfterEnd));
+
+        reminder.windows = vec![window(vec![6, 7], "09:00", "18:00")];
+        assert_eq!(reminder.window_skip_reason(at(10, 0)), Some(SkipReason::OutsideWindow));
+    }
+
+    #[test]
+    fn stored_reminder_with_invalid_fields_is_rejected() {
+        let stored = serde_json::to_value(reminder()).unwrap();
+        assert!(serde_json::from_value::<Reminder>(stored.clone()).is_ok());
+
+        let mut bad_schedule = stored.clone();
+        bad_schedule["schedule"] = json!({ "type": "interval", "minutes": 0 });
+        assert!(serde_json::from_value::<Reminder>(bad_schedule).is_err());
+
+        let mut inverted_window = stored;
+        inverted_window["windows"] = json!([{ "weekdays": [], "start": "18:00", "end": "08:00" }]);
+        assert!(serde_json::from_value::<Reminder>(inverted_window).is_err());
+    }
+
+    #[test]
+    fn unscheduled_reminder_cannot_be_resumed() {
+        let mut reminder = reminder();
+        reminder.schedule = Schedule::Unscheduled;
+        reminder.pause(None, false).unwrap();
+
+        assert!(reminder.resume().is_err());
+        assert!(!reminder.is_due(NOW));
+
+        reminder.set_schedule(Schedule::Interval { minutes: 30 });
+        reminder.resume().unwrap();
+    }
+
+    #[test]
+    fn equal_priorities_sort_by_title_in_both_directions() {
+        let titled = |title: &str, priority: Priority| {
+            let mut reminder = reminder();
+            reminder.title = title.to_string();
+            reminder.priority = priority;
+            reminder
+        };
+        let mut reminders = vec![
+            titled("b", Priority::Normal),
+            titled("c", Priority::High),
+            titled("a", Priority::Normal),
+            titled("d", Priority::High),
+        ];
+        let titles = |reminders: &[Reminder]| -> Vec<String> {
+            reminders.iter().map(|reminder| reminder.title.clone()).collect()
+        };
+        let query = |descending: Option<bool>| ReminderQuery {
+            sort_by: Some(ReminderSort::Priority),
+            descending,
+            ..ReminderQuery::default()
+        };
+
+        query(None).sort(&mut reminders);
+        assert_eq!(titles(&reminders), vec!["c", "d", "a", "b"]);
+        query(Some(false)).sort(&mut reminders);
+        assert_eq!(titles(&reminders), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn cancelled_reminder_cannot_be_paused() {
+        let mut reminder = reminder();
+        reminder.cancel().unwrap();
+
+        assert!(reminder.pause(Some(NOW), false).is_err());
+        assert_eq!(reminder.paused_until, None);
+    }
 }
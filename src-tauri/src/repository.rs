@@ -1,5 +1,12 @@
+use crate::cron_expr::parse_schedule;
 use crate::error::{AppError, AppResult};
-use crate::models::{Reminder, ReminderGroup};
+use crate::models::{
+    backfill_updated_at, AppSettings, Context, OccurrenceLogEntry, Reminder, ReminderGroup,
+    Routine, Tag,
+};
+use chrono::NaiveTime;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
@@ -8,6 +15,7 @@ pub trait DataRepository: Send + Sync {
     fn get_groups(&self) -> AppResult<Vec<ReminderGroup>>;
     fn add_group(&self, group: ReminderGroup) -> AppResult<()>;
     fn remove_group(&self, group_id: &str) -> AppResult<()>;
+    fn update_group(&self, group: &ReminderGroup) -> AppResult<()>;
     
     fn get_reminders(&self) -> AppResult<Vec<Reminder>>;
     fn add_reminder(&self, reminder: Reminder) -> AppResult<()>;
@@ -15,18 +23,72 @@ pub trait DataRepository: Send + Sync {
     fn remove_reminder(&self, reminder_id: &str) -> AppResult<()>;
     fn find_reminder(&self, reminder_id: &str) -> AppResult<Option<Reminder>>;
     fn find_reminders_by_group(&self, group_id: &str) -> AppResult<Vec<Reminder>>;
+    fn find_reminders_by_tag(&self, tag_id: &str) -> AppResult<Vec<Reminder>>;
+
+    fn get_routines(&self) -> AppResult<Vec<Routine>>;
+    fn add_routine(&self, routine: Routine) -> AppResult<()>;
+    fn update_routine(&self, routine: &Routine) -> AppResult<()>;
+    fn remove_routine(&self, routine_id: &str) -> AppResult<()>;
+    fn find_routine(&self, routine_id: &str) -> AppResult<Option<Routine>>;
+
+    fn get_contexts(&self) -> AppResult<Vec<Context>>;
+    fn add_context(&self, context: Context) -> AppResult<()>;
+    fn remove_context(&self, context_id: &str) -> AppResult<()>;
+
+    fn get_tags(&self) -> AppResult<Vec<Tag>>;
+    fn add_tag(&self, tag: Tag) -> AppResult<()>;
+    fn update_tag(&self, tag: &Tag) -> AppResult<()>;
+    fn remove_tag(&self, tag_id: &str) -> AppResult<()>;
+    fn find_tag(&self, tag_id: &str) -> AppResult<Option<Tag>>;
+
+    fn get_settings(&self) -> AppResult<AppSettings>;
+    fn update_settings(&self, settings: &AppSettings) -> AppResult<()>;
+
+    fn get_occurrence_log(&self) -> AppResult<Vec<OccurrenceLogEntry>>;
+    fn add_occurrence_log_entry(&self, entry: OccurrenceLogEntry) -> AppResult<()>;
+    fn update_occurrence_log_entry(&self, entry: &OccurrenceLogEntry) -> AppResult<()>;
+    fn replace_occurrence_log(&self, log: Vec<OccurrenceLogEntry>) -> AppResult<()>;
+
+    /// 标记存在未保存的修改，调度线程修改数据后调用，由维护循环统一保存
+    fn mark_unsaved(&self);
+    /// 取出并清除未保存标记
+    fn take_unsaved(&self) -> bool;
+}
+
+/// 持久化存储中的全部数据
+#[derive(Debug, Clone, Default)]
+pub struct AppData {
+    pub groups: Vec<ReminderGroup>,
+    pub reminders: Vec<Reminder>,
+    pub routines: Vec<Routine>,
+    pub contexts: Vec<Context>,
+    pub tags: Vec<Tag>,
+    pub settings: AppSettings,
+    pub occurrence_log: Vec<OccurrenceLogEntry>,
 }
 
 pub struct InMemoryRepository {
     groups: Arc<RwLock<Vec<ReminderGroup>>>,
     reminders: Arc<RwLock<Vec<Reminder>>>,
+    routines: Arc<RwLock<Vec<Routine>>>,
+    contexts: Arc<RwLock<Vec<Context>>>,
+    tags: Arc<RwLock<Vec<Tag>>>,
+    settings: Arc<RwLock<AppSettings>>,
+    occurrence_log: Arc<RwLock<Vec<OccurrenceLogEntry>>>,
+    unsaved: AtomicBool,
 }
 
 impl InMemoryRepository {
-    pub fn new(groups: Vec<ReminderGroup>, reminders: Vec<Reminder>) -> Self {
+    pub fn new(data: AppData) -> Self {
         Self {
-            groups: Arc::new(RwLock::new(groups)),
-            reminders: Arc::new(RwLock::new(reminders)),
+            groups: Arc::new(RwLock::new(data.groups)),
+            reminders: Arc::new(RwLock::new(data.reminders)),
+            routines: Arc::new(RwLock::new(data.routines)),
+            contexts: Arc::new(RwLock::new(data.contexts)),
+            tags: Arc::new(RwLock::new(data.tags)),
+            settings: Arc::new(RwLock::new(data.settings)),
+            occurrence_log: Arc::new(RwLock::new(data.occurrence_log)),
+            unsaved: AtomicBool::new(false),
         }
     }
 }
@@ -58,6 +120,20 @@ impl DataRepository for InMemoryRepository {
         Ok(())
     }
 
+    fn update_group(&self, group: &ReminderGroup) -> AppResult<()> {
+        let mut groups = self
+            .groups
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write groups: {}", e)))?;
+
+        if let Some(existing) = groups.iter_mut().find(|g| g.id == group.id) {
+            *existing = group.clone();
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Group with id {} not found", group.id)))
+        }
+    }
+
     fn get_reminders(&self) -> AppResult<Vec<Reminder>> {
         let reminders = self
             .reminders
@@ -125,6 +201,310 @@ impl DataRepository for InMemoryRepository {
             .cloned()
             .collect())
     }
+
+    fn find_reminders_by_tag(&self, tag_id: &str) -> AppResult<Vec<Reminder>> {
+        let reminders = self
+            .reminders
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read reminders: {}", e)))?;
+
+        Ok(reminders
+            .iter()
+            .filter(|r| r.tags.iter().any(|tag| tag == tag_id))
+            .cloned()
+            .collect())
+    }
+
+    fn get_routines(&self) -> AppResult<Vec<Routine>> {
+        let routines = self
+            .routines
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read routines: {}", e)))?;
+        Ok(routines.clone())
+    }
+
+    fn add_routine(&self, routine: Routine) -> AppResult<()> {
+        let mut routines = self
+            .routines
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write routines: {}", e)))?;
+        routines.push(routine);
+        Ok(())
+    }
+
+    fn update_routine(&self, routine: &Routine) -> AppResult<()> {
+        let mut routines = self
+            .routines
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write routines: {}", e)))?;
+
+        if let Some(existing) = routines.iter_mut().find(|r| r.id == routine.id) {
+            *existing = routine.clone();
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Routine with id {} not found", routine.id)))
+        }
+    }
+
+    fn remove_routine(&self, routine_id: &str) -> AppResult<()> {
+        let mut routines = self
+            .routines
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write routines: {}", e)))?;
+
+        let initial_len = routines.len();
+        routines.retain(|r| r.id != routine_id);
+
+        if routines.len() == initial_len {
+            Err(AppError::NotFound(format!("Routine with id {} not found", routine_id)))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn find_routine(&self, routine_id: &str) -> AppResult<Option<Routine>> {
+        let routines = self
+            .routines
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read routines: {}", e)))?;
+
+        Ok(routines.iter().find(|r| r.id == routine_id).cloned())
+    }
+
+    fn get_contexts(&self) -> AppResult<Vec<Context>> {
+        let contexts = self
+            .contexts
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read contexts: {}", e)))?;
+        Ok(contexts.clone())
+    }
+
+    fn add_context(&self, context: Context) -> AppResult<()> {
+        let mut contexts = self
+            .contexts
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write contexts: {}", e)))?;
+        contexts.push(context);
+        Ok(())
+    }
+
+    fn remove_context(&self, context_id: &str) -> AppResult<()> {
+        let mut contexts = self
+            .contexts
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write contexts: {}", e)))?;
+
+        let initial_len = contexts.len();
+        contexts.retain(|c| c.id != context_id);
+
+        if contexts.len() == initial_len {
+            Err(AppError::NotFound(format!("Context with id {} not found", context_id)))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_tags(&self) -> AppResult<Vec<Tag>> {
+        let tags = self
+            .tags
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read tags: {}", e)))?;
+        Ok(tags.clone())
+    }
+
+    fn add_tag(&self, tag: Tag) -> AppResult<()> {
+        let mut tags = self
+            .tags
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write tags: {}", e)))?;
+        tags.push(tag);
+        Ok(())
+    }
+
+    fn update_tag(&self, tag: &Tag) -> AppResult<()> {
+        let mut tags = self
+            .tags
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write tags: {}", e)))?;
+
+        if let Some(existing) = tags.iter_mut().find(|t| t.id == tag.id) {
+            *existing = tag.clone();
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Tag with id {} not found", tag.id)))
+        }
+    }
+
+    fn remove_tag(&self, tag_id: &str) -> AppResult<()> {
+        let mut tags = self
+            .tags
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write tags: {}", e)))?;
+
+        let initial_len = tags.len();
+        tags.retain(|t| t.id != tag_id);
+
+        if tags.len() == initial_len {
+            Err(AppError::NotFound(format!("Tag with id {} not found", tag_id)))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn find_tag(&self, tag_id: &str) -> AppResult<Option<Tag>> {
+        let tags = self
+            .tags
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read tags: {}", e)))?;
+
+        Ok(tags.iter().find(|t| t.id == tag_id).cloned())
+    }
+
+    fn get_settings(&self) -> AppResult<AppSettings> {
+        let settings = self
+            .settings
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read settings: {}", e)))?;
+        Ok(settings.clone())
+    }
+
+    fn update_settings(&self, settings: &AppSettings) -> AppResult<()> {
+        let mut existing = self
+            .settings
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write settings: {}", e)))?;
+        *existing = settings.clone();
+        Ok(())
+    }
+
+    fn get_occurrence_log(&self) -> AppResult<Vec<OccurrenceLogEntry>> {
+        let log = self
+            .occurrence_log
+            .read()
+            .map_err(|e| AppError::DataAccess(format!("Failed to read occurrence log: {}", e)))?;
+        Ok(log.clone())
+    }
+
+    fn add_occurrence_log_entry(&self, entry: OccurrenceLogEntry) -> AppResult<()> {
+        let mut log = self
+            .occurrence_log
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write occurrence log: {}", e)))?;
+        log.push(entry);
+        Ok(())
+    }
+
+    fn update_occurrence_log_entry(&self, entry: &OccurrenceLogEntry) -> AppResult<()> {
+        let mut log = self
+            .occurrence_log
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write occurrence log: {}", e)))?;
+
+        if let Some(existing) = log.iter_mut().find(|e| e.id == entry.id) {
+            *existing = entry.clone();
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Occurrence log entry with id {} not found", entry.id)))
+        }
+    }
+
+    fn replace_occurrence_log(&self, entries: Vec<OccurrenceLogEntry>) -> AppResult<()> {
+        let mut log = self
+            .occurrence_log
+            .write()
+            .map_err(|e| AppError::DataAccess(format!("Failed to write occurrence log: {}", e)))?;
+        *log = entries;
+        Ok(())
+    }
+
+    fn mark_unsaved(&self) {
+        self.unsaved.store(true, Ordering::SeqCst);
+    }
+
+    fn take_unsaved(&self) -> bool {
+        self.unsaved.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// 旧数据的调度信息分散在 cron_expression、schedule_kind 和 start_at/end_at 中，
+/// 转换为 schedule 和每日时间窗口，返回是否有修改
+fn migrate_legacy_schedule(value: &mut Value) -> bool {
+    let Some(reminder) = value.as_object_mut() else {
+        return false;
+    };
+    if reminder.contains_key("schedule") {
+        return false;
+    }
+
+    // 按完成间隔和截止提醒的旧格式与新格式相同；
+    // cron 表达式为空或无法解析的旧提醒从未被调度，改为无计划并暂停，不注册任务
+    let cron_expression = reminder.remove("cron_expression");
+    let schedule = match (reminder.remove("schedule_kind"), cron_expression) {
+        (Some(kind), _) if kind.get("type").is_some_and(|kind_type| kind_type != "cron") => kind,
+        (_, Some(Value::String(expression))) if parse_schedule(expression.trim()).is_ok() => {
+            json!({ "type": "cron", "expression": expression })
+        }
+        (_, expression) => {
+            if let Some(Value::String(expression)) = expression.filter(|e| e != "") {
+                eprintln!("Pausing legacy reminder with invalid cron expression {}", expression);
+            }
+            reminder.insert("is_paused".to_string(), Value::Bool(true));
+            if reminder.get("status").and_then(Value::as_str) == Some("active") {
+                reminder.insert("status".to_string(), json!("paused"));
+            }
+            json!({ "type": "unscheduled" })
+        }
+    };
+    reminder.insert("schedule".to_string(), schedule);
+
+    let start_at = reminder.remove("start_at");
+    let end_at = reminder.remove("end_at");
+    let start = start_at.as_ref().and_then(Value::as_str);
+    let end = end_at.as_ref().and_then(Value::as_str);
+    let has_windows = reminder
+        .get("windows")
+        .and_then(Value::as_array)
+        .is_some_and(|windows| !windows.is_empty());
+    if !has_windows && (start.is_some() || end.is_some()) {
+        let start = start.unwrap_or("00:00");
+        let end = end.unwrap_or("23:59");
+        let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").ok();
+        let valid = matches!((parse(start), parse(end)), (Some(start), Some(end)) if start < end);
+        if valid {
+            let window = json!({ "weekdays": [], "start": start, "end": end });
+            reminder.insert("windows".to_string(), json!([window]));
+        } else {
+            eprintln!("Dropping invalid legacy time window {}-{}", start, end);
+        }
+    }
+    true
+}
+
+/// 补齐旧数据缺少的创建、修改时间，返回是否有修改
+fn backfill_timestamps(data: &mut AppData, now: i64) -> bool {
+    let mut migrated = false;
+    for reminder in &mut data.reminders {
+        let first_logged_at = data
+            .occurrence_log
+            .iter()
+            .filter(|entry| entry.reminder_id == reminder.id)
+            .map(|entry| entry.scheduled_at)
+            .min();
+        migrated |= reminder.backfill_timestamps(first_logged_at, now);
+    }
+    for group in &mut data.groups {
+        migrated |= backfill_updated_at(&mut group.updated_at, group.created_at);
+    }
+    for routine in &mut data.routines {
+        migrated |= backfill_updated_at(&mut routine.updated_at, routine.created_at);
+    }
+    for context in &mut data.contexts {
+        migrated |= backfill_updated_at(&mut context.updated_at, context.created_at);
+    }
+    for tag in &mut data.tags {
+        migrated |= backfill_updated_at(&mut tag.updated_at, tag.created_at);
+    }
+    migrated
 }
 
 pub struct PersistenceManager {
@@ -136,7 +516,7 @@ impl PersistenceManager {
         Self { app_handle }
     }
 
-    pub fn load_data(&self) -> AppResult<(Vec<ReminderGroup>, Vec<Reminder>)> {
+    pub fn load_data(&self) -> AppResult<AppData> {
         let store = self
             .app_handle
             .store("reminders.json")
@@ -147,30 +527,108 @@ impl PersistenceManager {
             .and_then(|value| serde_json::from_value(value).ok())
             .unwrap_or_default();
 
-        let reminders: Vec<Reminder> = store
+        // 逐条解析提醒，单条数据无效时不影响其他提醒
+        let raw_reminders: Vec<Value> = store
             .get("reminders")
             .and_then(|value| serde_json::from_value(value).ok())
             .unwrap_or_default();
+        let mut migrated = false;
+        let mut reminders: Vec<Reminder> = Vec::new();
+        for mut value in raw_reminders {
+            migrated |= migrate_legacy_schedule(&mut value);
+            match serde_json::from_value::<Reminder>(value) {
+                Ok(mut reminder) => {
+                    reminder.migrate_legacy_status();
+                    reminders.push(reminder);
+                }
+                Err(e) => eprintln!("Skipping invalid reminder: {}", e),
+            }
+        }
+
+        let routines: Vec<Routine> = store
+            .get("routines")
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
 
-        Ok((groups, reminders))
+        let contexts: Vec<Context> = store
+            .get("contexts")
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+
+        let tags: Vec<Tag> = store
+            .get("tags")
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+
+        let settings: AppSettings = store
+            .get("settings")
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+
+        let occurrence_log: Vec<OccurrenceLogEntry> = store
+            .get("occurrence_log")
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+
+        let mut data = AppData {
+            groups,
+            reminders,
+            routines,
+            contexts,
+            tags,
+            settings,
+            occurrence_log,
+        };
+        migrated |= backfill_timestamps(&mut data, chrono::Utc::now().timestamp());
+
+        // 迁移结果立即保存，避免每次启动补齐的时间不同
+        if migrated {
+            self.save_data(&data)?;
+        }
+        Ok(data)
     }
 
-    pub fn save_data(&self, groups: &[ReminderGroup], reminders: &[Reminder]) -> AppResult<()> {
+    pub fn save_data(&self, data: &AppData) -> AppResult<()> {
         let store = self
             .app_handle
             .store("reminders.json")
             .map_err(|e| AppError::Persistence(format!("Failed to access store: {}", e)))?;
 
         store
-            .set("groups", serde_json::to_value(groups).map_err(|e| {
+            .set("groups", serde_json::to_value(&data.groups).map_err(|e| {
                 AppError::Persistence(format!("Failed to serialize groups: {}", e))
             })?);
 
         store
-            .set("reminders", serde_json::to_value(reminders).map_err(|e| {
+            .set("reminders", serde_json::to_value(&data.reminders).map_err(|e| {
                 AppError::Persistence(format!("Failed to serialize reminders: {}", e))
             })?);
 
+        store
+            .set("routines", serde_json::to_value(&data.routines).map_err(|e| {
+                AppError::Persistence(format!("Failed to serialize routines: {}", e))
+            })?);
+
+        store
+            .set("contexts", serde_json::to_value(&data.contexts).map_err(|e| {
+                AppError::Persistence(format!("Failed to serialize contexts: {}", e))
+            })?);
+
+        store
+            .set("tags", serde_json::to_value(&data.tags).map_err(|e| {
+                AppError::Persistence(format!("Failed to serialize tags: {}", e))
+            })?);
+
+        store
+            .set("settings", serde_json::to_value(&data.settings).map_err(|e| {
+                AppError::Persistence(format!("Failed to serialize settings: {}", e))
+            })?);
+
+        store
+            .set("occurrence_log", serde_json::to_value(&data.occurrence_log).map_err(|e| {
+                AppError::Persistence(format!("Failed to serialize occurrence log: {}", e))
+            })?);
+
         store
             .save()
             .map_err(|e| AppError::Persistence(format!("Failed to save store: {}", e)))?;
@@ -178,3 +636,184 @@ impl PersistenceManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{JobRunResult, ReminderStatus, Schedule};
+    use crate::scheduler::schedule_for;
+
+    /// 旧版本保存的提醒：没有 schedule、created_at 和 status
+    fn legacy_reminder(fields: Value) -> Value {
+        let mut value = json!({
+            "id": "r1",
+            "title": "喝水",
+            "color": "#007AFF",
+            "group_id": "default",
+            "last_triggered": null,
+            "is_cancelled": false,
+            "is_deleted": false,
+            "is_paused": false,
+            "description": null,
+            "start_at": null,
+            "end_at": null,
+        });
+        if let (Some(value), Some(fields)) = (value.as_object_mut(), fields.as_object()) {
+            value.extend(fields.clone());
+        }
+        value
+    }
+
+    fn load(mut value: Value) -> Reminder {
+        assert!(migrate_legacy_schedule(&mut value));
+        let mut reminder: Reminder = serde_json::from_value(value).unwrap();
+        reminder.migrate_legacy_status();
+        reminder
+    }
+
+    #[test]
+    fn empty_cron_is_paused_without_a_job() {
+        let reminder = load(legacy_reminder(json!({ "cron_expression": "" })));
+
+        assert_eq!(reminder.schedule, Schedule::Unscheduled);
+        assert_eq!(reminder.status, ReminderStatus::Paused);
+        assert!(reminder.is_paused);
+        assert_eq!(reminder.created_at, 0);
+        assert!(schedule_for(&reminder).is_err());
+    }
+
+    #[test]
+    fn missing_cron_is_paused_without_a_job() {
+        let reminder = load(legacy_reminder(json!({})));
+
+        assert_eq!(reminder.schedule, Schedule::Unscheduled);
+        assert_eq!(reminder.status, ReminderStatus::Paused);
+        assert!(schedule_for(&reminder).is_err());
+    }
+
+    #[test]
+    fn invalid_cron_is_paused_without_a_job() {
+        let mut reminder = load(legacy_reminder(json!({ "cron_expression": "not a schedule" })));
+
+        assert_eq!(reminder.schedule, Schedule::Unscheduled);
+        assert_eq!(reminder.status, ReminderStatus::Paused);
+        assert!(reminder.resume().is_err());
+    }
+
+    #[test]
+    fn inverted_daily_times_are_dropped() {
+        let reminder = load(legacy_reminder(json!({
+            "cron_expression": "0 0 9 * * *",
+            "start_at": "22:00",
+            "end_at": "06:00",
+        })));
+
+        assert!(reminder.windows.is_empty());
+    }
+
+    #[test]
+    fn missing_cron_keeps_cancelled_status() {
+        let reminder = load(legacy_reminder(json!({ "is_cancelled": true })));
+
+        assert_eq!(reminder.status, ReminderStatus::Cancelled);
+    }
+
+    #[test]
+    fn cron_and_daily_times_become_schedule_and_window() {
+        let reminder = load(legacy_reminder(json!({
+            "cron_expression": "0 0 9 * * *",
+            "start_at": "08:00",
+        })));
+
+        assert_eq!(reminder.schedule, Schedule::Cron { expression: "0 0 9 * * *".to_string() });
+        assert_eq!(reminder.status, ReminderStatus::Active);
+        assert_eq!(reminder.windows.len(), 1);
+        assert_eq!(reminder.windows[0].start.format("%H:%M").to_string(), "08:00");
+        assert_eq!(reminder.windows[0].end.format("%H:%M").to_string(), "23:59");
+    }
+
+    #[test]
+    fn legacy_schedule_kind_is_kept() {
+        let reminder = load(legacy_reminder(json!({
+            "cron_expression": null,
+            "schedule_kind": { "type": "after_completion", "interval_days": 3 },
+        })));
+
+        assert_eq!(reminder.schedule, Schedule::AfterCompletion { interval_days: 3 });
+        assert_eq!(reminder.status, ReminderStatus::Active);
+    }
+
+    #[test]
+    fn migrated_reminder_is_untouched() {
+        let mut value = legacy_reminder(json!({ "cron_expression": "0 0 9 * * *" }));
+        assert!(migrate_legacy_schedule(&mut value));
+        let migrated = value.clone();
+
+        assert!(!migrate_legacy_schedule(&mut value));
+        assert_eq!(value, migrated);
+    }
+
+    fn legacy_data() -> AppData {
+        let group: ReminderGroup = serde_json::from_value(json!({
+            "id": "default",
+            "name": "默认",
+            "color": "#007AFF",
+            "start_at": 1_000,
+        }))
+        .unwrap();
+        let triggered = load(legacy_reminder(json!({
+            "cron_expression": "0 0 9 * * *",
+            "last_triggered": 5_000,
+        })));
+        let mut logged = load(legacy_reminder(json!({ "cron_expression": "0 0 9 * * *" })));
+        logged.id = "r2".to_string();
+        let mut unused = logged.clone();
+        unused.id = "r3".to_string();
+
+        AppData {
+            groups: vec![group],
+            reminders: vec![triggered, logged, unused],
+            occurrence_log: vec![
+                OccurrenceLogEntry::new("r2", 4_000, JobRunResult::Notified),
+                OccurrenceLogEntry::new("r2", 3_000, JobRunResult::Notified),
+            ],
+            ..AppData::default()
+        }
+    }
+
+    #[test]
+    fn backfill_uses_earliest_known_time() {
+        let mut data = legacy_data();
+
+        assert!(backfill_timestamps(&mut data, 9_000));
+
+        let times: Vec<(i64, i64)> = data
+            .reminders
+            .iter()
+            .map(|reminder| (reminder.created_at, reminder.updated_at))
+            .collect();
+        assert_eq!(times, vec![(5_000, 5_000), (3_000, 3_000), (9_000, 9_000)]);
+        assert_eq!(data.groups[0].created_at, 1_000);
+        assert_eq!(data.groups[0].updated_at, 1_000);
+    }
+
+    #[test]
+    fn backfill_is_idempotent() {
+        let mut data = legacy_data();
+        assert!(backfill_timestamps(&mut data, 9_000));
+        let first: Vec<(i64, i64)> = data
+            .reminders
+            .iter()
+            .map(|reminder| (reminder.created_at, reminder.updated_at))
+            .collect();
+
+        assert!(!backfill_timestamps(&mut data, 20_000));
+        let second: Vec<(i64, i64)> = data
+            .reminders
+            .iter()
+            .map(|reminder| (reminder.created_at, reminder.updated_at))
+            .collect();
+        assert_eq!(first, second);
+        assert_eq!(data.groups[0].updated_at, 1_000);
+    }
+}
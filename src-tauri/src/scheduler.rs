@@ -1,36 +1,65 @@
+use crate::cron_expr::{parse_schedule, CronExpr};
 use crate::error::{AppError, AppResult};
-use crate::models::Reminder;
+use crate::models::{
+    DeadlineAlert, JobRunRecord, JobRunResult, OccurrenceLogEntry, OccurrenceOverride, Priority,
+    Reminder, ReminderAttachmentsEvent, Schedule, ScheduledJobInfo, SkipReason,
+};
 use crate::repository::DataRepository;
 use crate::utils::get_current_time;
-use english_to_cron::str_cron_syntax;
-use job_scheduler_ng::{Job, JobScheduler};
+use job_scheduler_ng::{Job, JobScheduler, Schedule as JobSchedule};
 use std::collections::HashMap;
+use chrono::TimeZone;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
 
 extern crate uuid;
 
+/// 带附件的提醒触发时发给前端的事件
+pub const ATTACHMENTS_EVENT: &str = "reminder-attachments";
+
+/// 调度器中实际存在的任务，用于诊断
+struct JobEntry {
+    reminder_id: String,
+    schedule: CronExpr,
+}
+
 pub struct SendSyncJobScheduler {
     scheduler: JobScheduler<'static>,
+    entries: HashMap<uuid::Uuid, JobEntry>,
 }
 
 impl SendSyncJobScheduler {
     fn new() -> Self {
         Self {
             scheduler: JobScheduler::new(),
+            entries: HashMap::new(),
         }
     }
 
-    fn add(&mut self, job: Job<'static>) -> uuid::Uuid {
-        self.scheduler.add(job)
+    fn add(&mut self, reminder_id: &str, schedule: CronExpr, job: Job<'static>) -> uuid::Uuid {
+        let job_id = self.scheduler.add(job);
+        self.entries.insert(
+            job_id,
+            JobEntry {
+                reminder_id: reminder_id.to_string(),
+                schedule,
+            },
+        );
+        job_id
     }
 
     fn remove(&mut self, id: uuid::Uuid) -> bool {
+        self.entries.remove(&id);
         self.scheduler.remove(id)
     }
 
+    /// 添加不属于任何提醒的内部任务，不参与诊断
+    fn add_system_job(&mut self, job: Job<'static>) -> uuid::Uuid {
+        self.scheduler.add(job)
+    }
+
     fn tick(&mut self) {
         self.scheduler.tick();
     }
@@ -46,9 +75,59 @@ impl Debug for SendSyncJobScheduler {
 unsafe impl Send for SendSyncJobScheduler {}
 unsafe impl Sync for SendSyncJobScheduler {}
 
+/// 根据提醒的调度方式生成调度计划
+pub fn schedule_for(reminder: &Reminder) -> AppResult<CronExpr> {
+    match &reminder.schedule {
+        Schedule::Cron { expression } => parse_schedule(expression),
+        Schedule::Once { at } => {
+            // 单次提醒的时间已过时不再补发
+            if *at <= chrono::Utc::now().timestamp() {
+                return Err(AppError::Validation(format!(
+                    "One-time reminder {} is already past its trigger time",
+                    reminder.id
+                )));
+            }
+            once_at_or_next_minute(*at)
+        }
+        Schedule::AfterCompletion { .. } => {
+            let next_due_at = reminder.next_due_at
+                .ok_or_else(|| AppError::Validation("Next due time is required".to_string()))?;
+            once_at_or_next_minute(next_due_at)
+        }
+        // 间隔提醒和截止提醒每分钟检查一次是否到期
+        Schedule::Interval { .. } | Schedule::Deadline { .. } => CronExpr::parse("0 * * * * *"),
+        Schedule::Unscheduled => Err(AppError::Validation(format!(
+            "Reminder {} has no schedule",
+            reminder.id
+        ))),
+    }
+}
+
+/// 只在 at 触发一次；应用关闭期间已到期的提醒在下一分钟补发
+fn once_at_or_next_minute(at: i64) -> AppResult<CronExpr> {
+    let next_minute = (chrono::Utc::now().timestamp() / 60 + 1) * 60;
+    let due = chrono::Utc
+        .timestamp_opt(at.max(next_minute), 0)
+        .single()
+        .ok_or_else(|| AppError::Validation("Invalid trigger time".to_string()))?;
+    Ok(CronExpr::once_at(due))
+}
+
+/// 精确到分钟的本地当前时间，用于检查时间窗口
+fn local_minute() -> chrono::NaiveDateTime {
+    let (hour, minutes) = get_current_time();
+    let today = chrono::Local::now().date_naive();
+    today
+        .and_hms_opt(hour, minutes, 0)
+        .unwrap_or_else(|| chrono::Local::now().naive_local())
+}
+
 pub struct ReminderScheduler {
     scheduler: Arc<Mutex<SendSyncJobScheduler>>,
     job_ids: Arc<Mutex<HashMap<String, uuid::Uuid>>>,
+    last_runs: Arc<Mutex<HashMap<String, JobRunRecord>>>,
+    /// 执行后已不再活跃（如单次提醒完成）的提醒，其任务在下一次 tick 后移除
+    finished: Arc<Mutex<Vec<String>>>,
     app_handle: AppHandle,
     repository: Arc<dyn DataRepository>,
 }
@@ -58,135 +137,447 @@ impl ReminderScheduler {
         Self {
             scheduler: Arc::new(Mutex::new(SendSyncJobScheduler::new())),
             job_ids: Arc::new(Mutex::new(HashMap::new())),
+            last_runs: Arc::new(Mutex::new(HashMap::new())),
+            finished: Arc::new(Mutex::new(Vec::new())),
             app_handle,
             repository,
         }
     }
 
-    /// 添加新的提醒任务
+    /// 添加新的提醒任务，已存在的任务会被替换
     pub async fn add_reminder_job(&mut self, reminder: &Reminder) -> AppResult<()> {
-        let cron_expression = reminder.cron_expression.as_ref()
-            .ok_or_else(|| AppError::Validation("Cron expression is required".to_string()))?;
-
-        let reminder_id = reminder.id.clone();
-        let reminder_title = reminder.title.clone();
-
-        // 创建定时任务
-        let cron_expr = str_cron_syntax(cron_expression)
+        // 创建定时任务：job_scheduler_ng 只负责时分秒，日期字段在本地计算
+        let schedule = schedule_for(reminder)?;
+        let trigger: JobSchedule = schedule
+            .time_of_day_expression()
+            .parse()
             .map_err(|e| AppError::Scheduler(format!("Invalid cron expression: {}", e)))?;
 
         let app_handle = self.app_handle.clone();
-        let title_clone = reminder_title.clone();
         let repository = Arc::clone(&self.repository);
-        let reminder_id_clone = reminder_id.clone();
-
-        let job = Job::new(
-            cron_expr
-                .parse()
-                .map_err(|e| AppError::Scheduler(format!("Invalid cron expression: {}", e)))?,
-            move || {
-                // 实时检查 reminder 状态
-                if let Ok(Some(current_reminder)) = repository.find_reminder(&reminder_id_clone) {
-                    // 检查 reminder 是否处于活跃状态
-                    if !current_reminder.is_active() {
-                        println!(
-                            "Reminder {} is not active, skipping notification",
-                            current_reminder.title
-                        );
-                        return;
-                    }
-
-                    let (hour, minutes) = get_current_time();
-                    let now = chrono::NaiveTime::parse_from_str(
-                        format!("{}:{}", hour, minutes).as_str(),
-                        "%H:%M",
-                    )
-                    .ok();
-
-                    // 检查结束时间
-                    if let Some(end_at) = &current_reminder.end_at {
-                        let end_time = chrono::NaiveTime::parse_from_str(end_at, "%H:%M").ok();
-                        if end_time.is_some()
-                            && now.is_some()
-                            && now.unwrap() > end_time.unwrap()
-                        {
-                            println!(
-                                "Reminder {} has ended, skipping notification",
-                                current_reminder.title
-                            );
-                            return;
-                        }
-                    }
-
-                    // 检查开始时间
-                    if let Some(start_at) = &current_reminder.start_at {
-                        let start_time =
-                            chrono::NaiveTime::parse_from_str(start_at, "%H:%M").ok();
-                        if start_time.is_some()
-                            && now.is_some()
-                            && now.unwrap() < start_time.unwrap()
-                        {
-                            println!(
-                                "Reminder {} has not started yet, skipping notification",
-                                current_reminder.title
-                            );
-                            return;
-                        }
-                    }
+        let last_runs = Arc::clone(&self.last_runs);
+        let finished = Arc::clone(&self.finished);
+        let reminder_id = reminder.id.clone();
+        let job_schedule = schedule.clone();
+        let job_trigger = trigger.clone();
+
+        let job = Job::new(trigger, move || {
+            // 找出本次执行对应的计划触发时间
+            let now = chrono::Utc::now();
+            let occurrence = job_trigger
+                .after(&(now + chrono::Duration::seconds(1)))
+                .next_back()
+                .unwrap_or(now);
+
+            // 日期字段按本地日期判断，与时间窗口和每日计数一致
+            if !job_schedule.matches_date(occurrence.with_timezone(&chrono::Local).date_naive()) {
+                return;
+            }
 
-                    // 发送通知
-                    if let Err(e) = Self::send_notification_sync_internal(
-                        &app_handle,
-                        &title_clone,
-                    ) {
-                        eprintln!("Failed to send notification: {}", e);
-                    }
+            let result = Self::run_reminder_job(
+                &app_handle,
+                repository.as_ref(),
+                &reminder_id,
+                occurrence.timestamp(),
+            );
+
+            // 记录最近一次执行结果，并追加到触发记录
+            Self::record_occurrence(
+                repository.as_ref(),
+                &reminder_id,
+                occurrence.timestamp(),
+                &result,
+            );
+            if let Ok(mut last_runs) = last_runs.lock() {
+                last_runs.insert(
+                    reminder_id.clone(),
+                    JobRunRecord {
+                        ran_at: chrono::Utc::now().timestamp(),
+                        result,
+                    },
+                );
+            }
 
-                    // 更新 last_triggered 时间
-                    let mut updated_reminder = current_reminder.clone();
-                    updated_reminder.update_last_triggered();
-                    if let Err(e) = repository.update_reminder(&updated_reminder) {
-                        eprintln!("Failed to update reminder last_triggered: {}", e);
-                    }
-                } else {
-                    println!(
-                        "Reminder {} not found, skipping notification",
-                        reminder_id_clone
-                    );
+            // 执行期间调度器已加锁，不能在这里移除自己的任务
+            let inactive = repository
+                .find_reminder(&reminder_id)
+                .is_ok_and(|reminder| reminder.is_some_and(|r| !r.is_active()));
+            if inactive {
+                if let Ok(mut finished) = finished.lock() {
+                    finished.push(reminder_id.clone());
                 }
-            },
-        );
+            }
+        });
 
-        // 存储任务ID
         let mut scheduler = self
             .scheduler
             .lock()
             .map_err(|e| AppError::Internal(format!("Failed to lock scheduler: {}", e)))?;
 
-        let job_id = scheduler.add(job);
-
         let mut job_ids = self
             .job_ids
             .lock()
             .map_err(|e| AppError::Internal(format!("Failed to lock job_ids: {}", e)))?;
 
+        // 同一个提醒只保留一个任务，避免重复恢复时产生重复通知
+        if let Some(old_job_id) = job_ids.remove(&reminder.id) {
+            scheduler.remove(old_job_id);
+        }
+
+        // 存储任务ID
+        let job_id = scheduler.add(&reminder.id, schedule, job);
         job_ids.insert(reminder.id.clone(), job_id);
 
         Ok(())
     }
 
+    /// 任务触发时执行：实时检查 reminder 状态后发送通知
+    fn run_reminder_job(
+        app_handle: &AppHandle,
+        repository: &dyn DataRepository,
+        reminder_id: &str,
+        occurrence_at: i64,
+    ) -> JobRunResult {
+        let current_reminder = match repository.find_reminder(reminder_id) {
+            Ok(Some(reminder)) => reminder,
+            _ => {
+                println!("Reminder {} not found, skipping notification", reminder_id);
+                return JobRunResult::Skipped {
+                    reason: SkipReason::NotFound,
+                };
+            }
+        };
+
+        // 检查 reminder 是否处于活跃状态
+        if !current_reminder.is_active() {
+            println!(
+                "Reminder {} is not active, skipping notification",
+                current_reminder.title
+            );
+            return JobRunResult::Skipped {
+                reason: SkipReason::NotActive,
+            };
+        }
+
+        // 按分钟检查的计划未到时间
+        let now = chrono::Utc::now().timestamp();
+        if !current_reminder.is_due(now) {
+            return JobRunResult::Skipped {
+                reason: SkipReason::NotDue,
+            };
+        }
+
+        // 检查是否属于当前情境
+        if !Self::matches_active_context(repository, &current_reminder) {
+            println!(
+                "Reminder {} is not in the active context, skipping notification",
+                current_reminder.title
+            );
+            return JobRunResult::Skipped {
+                reason: SkipReason::InactiveContext,
+            };
+        }
+
+        // 本次触发被跳过或改期
+        if current_reminder.override_for(occurrence_at).is_some() {
+            println!(
+                "Occurrence of reminder {} at {} is overridden, skipping notification",
+                current_reminder.title, occurrence_at
+            );
+            return JobRunResult::Skipped {
+                reason: SkipReason::Overridden,
+            };
+        }
+
+        // 检查时间窗口
+        if let Some(reason) = current_reminder.window_skip_reason(local_minute()) {
+            println!(
+                "Reminder {} is outside its time window ({:?}), skipping notification",
+                current_reminder.title, reason
+            );
+            return JobRunResult::Skipped { reason };
+        }
+
+        // 检查每日通知上限
+        let today = chrono::Local::now().date_naive();
+        if current_reminder.daily_cap_reached(today) {
+            println!(
+                "Reminder {} reached its daily limit, skipping notification",
+                current_reminder.title
+            );
+            return JobRunResult::Skipped {
+                reason: SkipReason::DailyCapReached,
+            };
+        }
+
+        let mut updated_reminder = current_reminder;
+        let (title, body) = match updated_reminder.due_deadline_alert(now) {
+            Some(alert) => {
+                updated_reminder.mark_deadline_alert(now);
+                let title = match alert {
+                    DeadlineAlert::Stage(_) => "截止提醒",
+                    DeadlineAlert::Overdue => "逾期提醒",
+                };
+                (title, updated_reminder.deadline_message(now))
+            }
+            None => ("提醒", updated_reminder.title.clone()),
+        };
+
+        // 免打扰时段只通知紧急提醒；截止提醒的阶段未标记，结束后会补发
+        if Self::is_quiet_for(repository, updated_reminder.priority) {
+            println!(
+                "Quiet hours, skipping notification for {}",
+                updated_reminder.title
+            );
+            return JobRunResult::Skipped {
+                reason: SkipReason::QuietHours,
+            };
+        }
+
+        // 重复提醒每次触发重置清单，正文附上剩余项数
+        updated_reminder.begin_occurrence();
+        let body = updated_reminder.notification_body(&body);
+
+        // 假期模式：不发送通知，只记录本应触发的提醒；状态照常推进，避免结束后补发
+        if Self::record_vacation_miss(repository, &updated_reminder, occurrence_at) {
+            updated_reminder.update_last_triggered();
+            updated_reminder.finish_if_once();
+            if let Err(e) = repository.update_reminder(&updated_reminder) {
+                eprintln!("Failed to update reminder last_triggered: {}", e);
+            }
+            return JobRunResult::Skipped {
+                reason: SkipReason::Vacation,
+            };
+        }
+
+        // 发送通知
+        let result = match Self::send_reminder_notification(
+            app_handle,
+            &updated_reminder,
+            title,
+            &body,
+        ) {
+            Ok(()) => JobRunResult::Notified,
+            Err(e) => {
+                eprintln!("Failed to send notification: {}", e);
+                JobRunResult::Failed {
+                    error: e.to_string(),
+                }
+            }
+        };
+
+        // 更新 last_triggered 时间和当天计数
+        updated_reminder.update_last_triggered();
+        if matches!(result, JobRunResult::Notified) {
+            updated_reminder.record_daily_fire(today);
+            updated_reminder.mark_notified(now);
+            updated_reminder.finish_if_once();
+        }
+        if let Err(e) = repository.update_reminder(&updated_reminder) {
+            eprintln!("Failed to update reminder last_triggered: {}", e);
+        }
+        if updated_reminder.awaiting_ack_since.is_some() {
+            Self::refresh_tray_flag(app_handle, repository);
+        }
+
+        result
+    }
+
+    /// 追加一条触发记录，超出单个提醒的保留上限时丢弃最早的记录
+    fn record_occurrence(
+        repository: &dyn DataRepository,
+        reminder_id: &str,
+        scheduled_at: i64,
+        result: &JobRunResult,
+    ) {
+        // 提醒已不存在，或按分钟检查的计划未到时间时不记录
+        if matches!(
+            result,
+            JobRunResult::Skipped {
+                reason: SkipReason::NotFound | SkipReason::NotDue
+            }
+        ) {
+            return;
+        }
+
+        // 按分钟检查的计划在跳过期间每分钟都会执行，连续相同原因的跳过只记录一次
+        if let JobRunResult::Skipped { reason } = result {
+            let polled = repository.find_reminder(reminder_id).ok().flatten().is_some_and(|r| {
+                matches!(r.schedule, Schedule::Interval { .. } | Schedule::Deadline { .. })
+            });
+            let repeated = repository.get_occurrence_log().is_ok_and(|log| {
+                log.iter()
+                    .rev()
+                    .find(|entry| entry.reminder_id == reminder_id)
+                    .is_some_and(|entry| match &entry.result {
+                        JobRunResult::Skipped { reason: last } => last == reason,
+                        _ => false,
+                    })
+            });
+            if polled && repeated {
+                return;
+            }
+        }
+
+        let entry = OccurrenceLogEntry::new(reminder_id, scheduled_at, result.clone());
+        if let Err(e) = repository.add_occurrence_log_entry(entry) {
+            eprintln!("Failed to record occurrence of {}: {}", reminder_id, e);
+            return;
+        }
+
+        let retention = repository
+            .get_settings()
+            .map(|settings| settings.history_retention)
+            .unwrap_or_default();
+        let limit = retention.max_entries_per_reminder as usize;
+        if let Ok(log) = repository.get_occurrence_log() {
+            if log.iter().filter(|entry| entry.reminder_id == reminder_id).count() > limit {
+                let mut log = log;
+                retention.apply(&mut log, chrono::Utc::now().timestamp());
+                if let Err(e) = repository.replace_occurrence_log(log) {
+                    eprintln!("Failed to trim occurrence log: {}", e);
+                }
+            }
+        }
+        repository.mark_unsaved();
+    }
+
+    /// 当前是否处于免打扰时段，且该优先级不能无视免打扰
+    fn is_quiet_for(repository: &dyn DataRepository, priority: Priority) -> bool {
+        if priority.bypasses_quiet_hours() {
+            return false;
+        }
+        let time = local_minute().time();
+        repository
+            .get_settings()
+            .ok()
+            .and_then(|settings| settings.quiet_hours)
+            .is_some_and(|quiet_hours| quiet_hours.contains(time))
+    }
+
+    /// 重复通知未确认的重要提醒
+    fn dispatch_renotifications(app_handle: &AppHandle, repository: &dyn DataRepository) {
+        let reminders = match repository.get_reminders() {
+            Ok(reminders) => reminders,
+            Err(e) => {
+                eprintln!("Failed to load reminders for renotify: {}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let on_vacation = repository
+            .get_settings()
+            .is_ok_and(|settings| settings.is_on_vacation(now));
+        for mut reminder in reminders {
+            if !reminder.is_active() || !reminder.is_renotify_due(now) {
+                continue;
+            }
+            if on_vacation || Self::is_quiet_for(repository, reminder.priority) {
+                continue;
+            }
+
+            let body = reminder.notification_body(&reminder.title);
+            match Self::send_reminder_notification(app_handle, &reminder, "再次提醒", &body) {
+                Ok(()) => {
+                    reminder.update_last_triggered();
+                    reminder.renotify_count += 1;
+                    if let Err(e) = repository.update_reminder(&reminder) {
+                        eprintln!("Failed to update reminder {}: {}", reminder.id, e);
+                    }
+                    repository.mark_unsaved();
+                }
+                Err(e) => eprintln!("Failed to send notification: {}", e),
+            }
+        }
+    }
+
+    /// 有未确认的重要提醒时在托盘上标记
+    fn refresh_tray_flag(app_handle: &AppHandle, repository: &dyn DataRepository) {
+        let Some(tray) = app_handle.tray_by_id("main") else {
+            return;
+        };
+        let pending = repository
+            .get_reminders()
+            .map(|reminders| {
+                reminders
+                    .iter()
+                    .filter(|r| r.is_active() && r.awaiting_ack_since.is_some())
+                    .count()
+            })
+            .unwrap_or_default();
+
+        let (tooltip, title) = if pending > 0 {
+            (format!("Reminders App（{} 个重要提醒待确认）", pending), Some("●"))
+        } else {
+            ("Reminders App".to_string(), None)
+        };
+        if let Err(e) = tray.set_tooltip(Some(tooltip)).and_then(|_| tray.set_title(title)) {
+            eprintln!("Failed to update tray flag: {}", e);
+        }
+    }
+
+    /// 确认或修改提醒后刷新托盘标记
+    pub fn update_tray_flag(&self) {
+        Self::refresh_tray_flag(&self.app_handle, self.repository.as_ref());
+    }
+
+    /// 处于假期模式时记录一次错过的触发，返回是否处于假期模式
+    fn record_vacation_miss(
+        repository: &dyn DataRepository,
+        reminder: &Reminder,
+        occurrence_at: i64,
+    ) -> bool {
+        let mut settings = match repository.get_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!("Failed to load settings: {}", e);
+                return false;
+            }
+        };
+        if !settings.is_on_vacation(chrono::Utc::now().timestamp()) {
+            return false;
+        }
+
+        if let Some(vacation) = settings.vacation.as_mut() {
+            vacation.record_missed(reminder, occurrence_at);
+        }
+        if let Err(e) = repository.update_settings(&settings) {
+            eprintln!("Failed to record vacation miss: {}", e);
+        }
+        println!("Vacation mode is on, skipping notification for {}", reminder.title);
+        true
+    }
+
+    /// 提醒（或其分组）是否属于当前情境
+    fn matches_active_context(repository: &dyn DataRepository, reminder: &Reminder) -> bool {
+        let active_context = match repository.get_settings() {
+            Ok(settings) => settings.active_context_id,
+            Err(e) => {
+                eprintln!("Failed to load settings: {}", e);
+                None
+            }
+        };
+        let group = repository
+            .get_groups()
+            .ok()
+            .and_then(|groups| groups.into_iter().find(|g| g.id == reminder.group_id));
+        reminder.matches_context(group.as_ref(), active_context.as_deref())
+    }
+
     /// 移除提醒任务
     pub fn remove_reminder_job(&self, reminder_id: &str) -> AppResult<()> {
+        let mut scheduler = self
+            .scheduler
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock scheduler: {}", e)))?;
+
         let mut job_ids = self
             .job_ids
             .lock()
             .map_err(|e| AppError::Internal(format!("Failed to lock job_ids: {}", e)))?;
 
         if let Some(job_id) = job_ids.remove(reminder_id) {
-            let mut scheduler = self
-                .scheduler
-                .lock()
-                .map_err(|e| AppError::Internal(format!("Failed to lock scheduler: {}", e)))?;
             scheduler.remove(job_id);
             println!("Removed reminder job for: {}", reminder_id);
         } else {
@@ -196,46 +587,135 @@ impl ReminderScheduler {
         Ok(())
     }
 
+    /// 按任务ID移除任务，用于清理脱离跟踪的任务
+    pub fn remove_job(&self, job_id: uuid::Uuid) -> AppResult<bool> {
+        let mut scheduler = self
+            .scheduler
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock scheduler: {}", e)))?;
+
+        let mut job_ids = self
+            .job_ids
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock job_ids: {}", e)))?;
+
+        job_ids.retain(|_, id| *id != job_id);
+        Ok(scheduler.remove(job_id))
+    }
+
+    /// 列出调度器中的所有任务及其下次触发时间和最近执行结果
+    pub fn inspect_jobs(&self) -> AppResult<Vec<ScheduledJobInfo>> {
+        let scheduler = self
+            .scheduler
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock scheduler: {}", e)))?;
+
+        let job_ids = self
+            .job_ids
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock job_ids: {}", e)))?;
+
+        let last_runs = self
+            .last_runs
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock last_runs: {}", e)))?;
+
+        let mut jobs: Vec<ScheduledJobInfo> = scheduler
+            .entries
+            .iter()
+            .map(|(job_id, entry)| ScheduledJobInfo {
+                job_id: job_id.to_string(),
+                reminder_id: entry.reminder_id.clone(),
+                tracked: job_ids.get(&entry.reminder_id) == Some(job_id),
+                next_fire_at: entry
+                    .schedule
+                    .next_after(chrono::Utc::now(), &chrono::Local)
+                    .map(|time| time.timestamp()),
+                last_run: last_runs.get(&entry.reminder_id).cloned(),
+            })
+            .collect();
+
+        jobs.sort_by(|a, b| a.reminder_id.cmp(&b.reminder_id));
+        Ok(jobs)
+    }
+
+    /// 发送通知
+    pub fn send_notification(&self, title: &str, body: &str) -> AppResult<()> {
+        Self::send_notification_sync_internal(&self.app_handle, title, body)
+    }
+
+    /// 按优先级发送提醒通知：桌面端以标题前缀区分，移动端同时设置声音
+    fn send_reminder_notification(
+        app_handle: &AppHandle,
+        reminder: &Reminder,
+        title: &str,
+        body: &str,
+    ) -> AppResult<()> {
+        let priority = reminder.priority;
+        let title = format!("{}{}", priority.title_prefix(), title);
+        // 移动端点击通知时可通过 extra 中的提醒 ID 打开详情
+        let mut builder = app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .extra("reminder_id", &reminder.id);
+        if priority.is_silent() {
+            builder = builder.silent();
+        } else if priority.requires_acknowledgement() {
+            builder = builder.sound("default");
+        }
+        builder
+            .show()
+            .map_err(|e| AppError::Scheduler(format!("Failed to send notification: {}", e)))?;
+
+        // 桌面通知不支持按钮，通知前端展示可打开的附件
+        if !reminder.attachments.is_empty() {
+            let event = ReminderAttachmentsEvent {
+                reminder_id: reminder.id.clone(),
+                title: reminder.title.clone(),
+                attachments: reminder.attachments.clone(),
+            };
+            if let Err(e) = app_handle.emit(ATTACHMENTS_EVENT, event) {
+                eprintln!("Failed to emit attachments event: {}", e);
+            }
+        }
+
+        println!("Sent {:?} notification: {}", priority, body);
+        Ok(())
+    }
+
     /// 内部使用的发送通知方法
-    fn send_notification_sync_internal(app_handle: &AppHandle, title: &str) -> AppResult<()> {
+    fn send_notification_sync_internal(
+        app_handle: &AppHandle,
+        title: &str,
+        body: &str,
+    ) -> AppResult<()> {
         app_handle
             .notification()
             .builder()
-            .title("提醒")
-            .body(title)
+            .title(title)
+            .body(body)
             .show()
             .map_err(|e| AppError::Scheduler(format!("Failed to send notification: {}", e)))?;
 
-        println!("Sent notification: {}", title);
+        println!("Sent notification: {}", body);
         Ok(())
     }
 
     /// 恢复所有活跃的提醒任务
     pub async fn restore_reminder_jobs(&mut self, reminders: &[Reminder]) -> AppResult<()> {
-        let (hour, minutes) = get_current_time();
-
-        let now =
-            chrono::NaiveTime::parse_from_str(format!("{}:{}", hour, minutes).as_str(), "%H:%M")
-                .ok();
+        let now = local_minute();
 
         for reminder in reminders {
             // 只恢复活跃的提醒
             if reminder.is_active() {
-                let end_at = if let Some(end_at) = reminder.end_at.clone() {
-                    chrono::NaiveTime::parse_from_str(&end_at, "%H:%M").ok()
-                } else {
-                    None
-                };
-
-                // 如果提醒已经结束，跳过
-                if let (Some(end_time), Some(current_time)) = (end_at, now) {
-                    if end_time <= current_time {
-                        println!(
-                            "Skipping reminder {} as it has already ended at {}",
-                            reminder.title, end_time
-                        );
-                        continue;
-                    }
+                // 当前不在时间窗口内的提醒照常注册，任务会等到下一个窗口再通知
+                if let Some(reason) = reminder.window_skip_reason(now) {
+                    println!(
+                        "Reminder {} is outside its time window ({:?}), waiting for next window",
+                        reminder.title, reason
+                    );
                 }
 
                 if let Err(e) = self.add_reminder_job(reminder).await {
@@ -251,12 +731,203 @@ impl ReminderScheduler {
         Ok(())
     }
 
+    /// 触发已到时间的改期提醒，并清理过期的单次调整
+    fn dispatch_occurrence_overrides(
+        app_handle: &AppHandle,
+        repository: &dyn DataRepository,
+        last_runs: &Mutex<HashMap<String, JobRunRecord>>,
+    ) {
+        let reminders = match repository.get_reminders() {
+            Ok(reminders) => reminders,
+            Err(e) => {
+                eprintln!("Failed to load reminders for overrides: {}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        for mut reminder in reminders {
+            if reminder.occurrence_overrides.is_empty() {
+                continue;
+            }
+
+            // 到期的改期触发：发送通知，若原计划时间未到则转为跳过
+            let mut fired_at = None;
+            let mut remaining = Vec::new();
+            for occurrence_override in &reminder.occurrence_overrides {
+                match occurrence_override {
+                    OccurrenceOverride::Move {
+                        occurrence_at,
+                        new_time,
+                    } if *new_time <= now => {
+                        fired_at = Some(*new_time);
+                        if *occurrence_at >= now - 60 {
+                            remaining.push(OccurrenceOverride::Skip {
+                                occurrence_at: *occurrence_at,
+                            });
+                        }
+                    }
+                    o if o.is_expired(now) => {}
+                    o => remaining.push(o.clone()),
+                }
+            }
+
+            let today = chrono::Local::now().date_naive();
+            if let Some(scheduled_at) = fired_at.filter(|_| reminder.is_active()) {
+                let result = if reminder.daily_cap_reached(today) {
+                    JobRunResult::Skipped {
+                        reason: SkipReason::DailyCapReached,
+                    }
+                } else if !Self::matches_active_context(repository, &reminder) {
+                    JobRunResult::Skipped {
+                        reason: SkipReason::InactiveContext,
+                    }
+                } else if Self::is_quiet_for(repository, reminder.priority) {
+                    JobRunResult::Skipped {
+                        reason: SkipReason::QuietHours,
+                    }
+                } else if Self::record_vacation_miss(repository, &reminder, now) {
+                    JobRunResult::Skipped {
+                        reason: SkipReason::Vacation,
+                    }
+                } else {
+                    reminder.begin_occurrence();
+                    let body = reminder.notification_body(&reminder.title);
+                    match Self::send_reminder_notification(app_handle, &reminder, "提醒", &body) {
+                        Ok(()) => {
+                            reminder.update_last_triggered();
+                            reminder.record_daily_fire(today);
+                            reminder.mark_notified(now);
+                            JobRunResult::Notified
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to send notification: {}", e);
+                            JobRunResult::Failed {
+                                error: e.to_string(),
+                            }
+                        }
+                    }
+                };
+                Self::record_occurrence(repository, &reminder.id, scheduled_at, &result);
+                if let Ok(mut last_runs) = last_runs.lock() {
+                    last_runs.insert(reminder.id.clone(), JobRunRecord { ran_at: now, result });
+                }
+            }
+
+            if remaining != reminder.occurrence_overrides {
+                reminder.occurrence_overrides = remaining;
+                if let Err(e) = repository.update_reminder(&reminder) {
+                    eprintln!("Failed to update reminder overrides: {}", e);
+                }
+                repository.mark_unsaved();
+            }
+        }
+    }
+
+    /// 按计划自动开始例程，并触发到时间的例程步骤
+    fn dispatch_routines(app_handle: &AppHandle, repository: &dyn DataRepository) {
+        let routines = match repository.get_routines() {
+            Ok(routines) => routines,
+            Err(e) => {
+                eprintln!("Failed to load routines: {}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now();
+        let timestamp = now.timestamp();
+        // 假期模式下不自动开始例程，进行中的例程照常推进但不通知
+        let on_vacation = repository
+            .get_settings()
+            .is_ok_and(|settings| settings.is_on_vacation(timestamp));
+        for mut routine in routines {
+            let mut changed = false;
+
+            // 上一分钟内到达计划时间且未在进行中的例程自动开始
+            if routine.is_enabled && !routine.is_running() && !on_vacation {
+                let scheduled = routine
+                    .cron_expression
+                    .as_deref()
+                    .and_then(|expr| parse_schedule(expr).ok())
+                    .and_then(|schedule| {
+                        schedule.next_after(now - chrono::Duration::seconds(60), &chrono::Local)
+                    })
+                    .is_some_and(|next| {
+                        next <= now
+                            && routine
+                                .last_started_at
+                                .is_none_or(|last| last < next.timestamp())
+                    });
+                if scheduled && routine.start(timestamp).is_ok() {
+                    println!("Started routine {} on schedule", routine.name);
+                    changed = true;
+                }
+            }
+
+            if let Some((index, step)) = routine.due_step(timestamp) {
+                let body = format!("{}（{}/{}）", step.title, index + 1, routine.steps.len());
+                let title = format!("例程：{}", routine.name);
+                if on_vacation {
+                    println!("Vacation mode is on, skipping routine step {}", step.title);
+                } else if let Err(e) =
+                    Self::send_notification_sync_internal(app_handle, &title, &body)
+                {
+                    eprintln!("Failed to send notification: {}", e);
+                }
+                routine.mark_step_fired(timestamp);
+                changed = true;
+            }
+
+            if changed {
+                if let Err(e) = repository.update_routine(&routine) {
+                    eprintln!("Failed to update routine {}: {}", routine.id, e);
+                }
+                repository.mark_unsaved();
+            }
+        }
+    }
+
     /// 启动调度器
     pub fn start_scheduler(&self) {
+        // 每分钟处理一次单次调整、例程和重复通知
+        let app_handle = self.app_handle.clone();
+        let repository = Arc::clone(&self.repository);
+        let last_runs = Arc::clone(&self.last_runs);
+        let dispatch_job = Job::new(
+            "0 * * * * *".parse().expect("valid dispatch schedule"),
+            move || {
+                Self::dispatch_occurrence_overrides(&app_handle, repository.as_ref(), &last_runs);
+                Self::dispatch_routines(&app_handle, repository.as_ref());
+                Self::dispatch_renotifications(&app_handle, repository.as_ref());
+                Self::refresh_tray_flag(&app_handle, repository.as_ref());
+            },
+        );
+        if let Ok(mut sched) = self.scheduler.lock() {
+            sched.add_system_job(dispatch_job);
+        }
+
         let scheduler = Arc::clone(&self.scheduler);
+        let job_ids = Arc::clone(&self.job_ids);
+        let finished = Arc::clone(&self.finished);
         std::thread::spawn(move || loop {
             if let Ok(mut sched) = scheduler.lock() {
                 sched.tick();
+
+                // 移除本次 tick 中已结束的提醒任务
+                let finished_ids = finished
+                    .lock()
+                    .map(|mut ids| std::mem::take(&mut *ids))
+                    .unwrap_or_default();
+                if !finished_ids.is_empty() {
+                    if let Ok(mut job_ids) = job_ids.lock() {
+                        for reminder_id in finished_ids {
+                            if let Some(job_id) = job_ids.remove(&reminder_id) {
+                                sched.remove(job_id);
+                                println!("Removed finished reminder job for: {}", reminder_id);
+                            }
+                        }
+                    }
+                }
             }
             std::thread::sleep(std::time::Duration::from_millis(500));
         });
@@ -268,6 +939,8 @@ impl Clone for ReminderScheduler {
         Self {
             scheduler: Arc::clone(&self.scheduler),
             job_ids: Arc::clone(&self.job_ids),
+            last_runs: Arc::clone(&self.last_runs),
+            finished: Arc::clone(&self.finished),
             app_handle: self.app_handle.clone(),
             repository: Arc::clone(&self.repository),
         }
@@ -279,3 +952,42 @@ impl Debug for ReminderScheduler {
         f.debug_struct("ReminderScheduler").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn once_reminder(at: i64) -> Reminder {
+        Reminder::new(
+            "t".to_string(),
+            "#007AFF".to_string(),
+            "default".to_string(),
+            Schedule::Once { at },
+            None,
+        )
+    }
+
+    #[test]
+    fn past_once_is_not_scheduled() {
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(schedule_for(&once_reminder(0)).is_err());
+        assert!(schedule_for(&once_reminder(now - 60)).is_err());
+        assert!(schedule_for(&once_reminder(now + 3600)).is_ok());
+    }
+
+    #[test]
+    fn after_completion_fires_at_next_due_time() {
+        let now = chrono::Utc::now().timestamp();
+        let mut reminder = once_reminder(now + 3600);
+        reminder.schedule = Schedule::AfterCompletion { interval_days: 2 };
+        assert!(schedule_for(&reminder).is_err());
+
+        let due = (now / 60 + 120) * 60;
+        reminder.next_due_at = Some(due);
+        let expr = schedule_for(&reminder).unwrap();
+        let after = chrono::Utc.timestamp_opt(now, 0).unwrap();
+        let next = expr.next_after(after, &chrono::Utc).unwrap();
+        assert_eq!(next.timestamp(), due);
+    }
+}
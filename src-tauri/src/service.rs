@@ -1,14 +1,41 @@
+use crate::attachments::AttachmentStore;
+use crate::cron_expr::{parse_schedule, CronExpr};
 use crate::error::{AppError, AppResult};
-use crate::models::{CreateGroupRequest, CreateReminderRequest, Reminder, ReminderGroup, UpdateReminderRequest};
-use crate::repository::{DataRepository, PersistenceManager};
-use crate::scheduler::ReminderScheduler;
+use crate::models::{
+    AdherenceStats, AppSettings, Attachment, AttachmentKind, BulkFailure, BulkResult, Context,
+    CreateGroupRequest, CreateReminderRequest, CreateRoutineRequest, DuplicatedGroup, GroupStats,
+    HistoryRetention, Occurrence, OccurrenceList, OccurrenceLogEntry, OccurrenceOverride,
+    OccurrenceResponse, PauseReminderRequest, QuietHours, ReconcileReport, Reminder, ReminderGroup,
+    ReminderQuery, ReminderStats, ReminderStatus, Routine, SavedReminder, Schedule, ScheduleWarning,
+    ScheduledJobInfo, SchedulerDiagnostics, SchedulerIssue, StatsReport, Tag, TagAction,
+    UpdateReminderRequest, UpdateRoutineRequest, VacationMode, VacationSummary,
+};
+use crate::utils::parse_local_datetime;
+use crate::repository::{AppData, DataRepository, PersistenceManager};
+use crate::scheduler::{schedule_for, ReminderScheduler};
+use crate::validation::{self, References};
+use chrono::{Local, NaiveDate, TimeZone, Timelike};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// 一次查询最多返回的触发数量
+const MAX_OCCURRENCES: usize = 5000;
+/// 触发查询的最长时间范围
+const MAX_OCCURRENCE_RANGE_SECONDS: i64 = 366 * 86400;
+
+/// 冲突检测向后检查的时间范围
+const CONFLICT_LOOKAHEAD_SECONDS: i64 = 7 * 86400;
+/// 同一分钟内允许触发的提醒数量
+const MAX_REMINDERS_PER_MINUTE: usize = 2;
+/// 全局每小时允许的触发次数
+const MAX_FIRES_PER_HOUR: usize = 30;
+
 pub struct ReminderService {
     repository: Arc<dyn DataRepository>,
     scheduler: Arc<RwLock<ReminderScheduler>>,
     persistence: Arc<PersistenceManager>,
+    attachments: Arc<AttachmentStore>,
 }
 
 impl ReminderService {
@@ -16,11 +43,13 @@ impl ReminderService {
         repository: Arc<dyn DataRepository>,
         scheduler: Arc<RwLock<ReminderScheduler>>,
         persistence: Arc<PersistenceManager>,
+        attachments: Arc<AttachmentStore>,
     ) -> Self {
         Self {
             repository,
             scheduler,
             persistence,
+            attachments,
         }
     }
 
@@ -29,7 +58,11 @@ impl ReminderService {
     }
 
     pub async fn create_group(&self, request: CreateGroupRequest) -> AppResult<ReminderGroup> {
-        let group = ReminderGroup::new(request.name, request.color);
+        validation::validate_create_group(&request, &self.references()?)?;
+        let mut group = ReminderGroup::new(request.name, request.color);
+        if let Some(contexts) = request.contexts {
+            group.contexts = contexts;
+        }
         self.repository.add_group(group.clone())?;
         self.save_data().await?;
         Ok(group)
@@ -52,27 +85,87 @@ impl ReminderService {
         self.repository.remove_group(group_id)?;
         for reminder in &reminders_to_delete {
             self.repository.remove_reminder(&reminder.id)?;
+            self.attachments.remove_reminder_files(&reminder.id);
         }
+        let mut log = self.repository.get_occurrence_log()?;
+        log.retain(|entry| !reminders_to_delete.iter().any(|r| r.id == entry.reminder_id));
+        self.repository.replace_occurrence_log(log)?;
 
         self.save_data().await?;
         println!("Deleted group with ID: {}", group_id);
         Ok(())
     }
 
+    /// 复制分组及组内未删除的提醒，paused 为 true 时提醒副本创建为暂停状态
+    pub async fn duplicate_group(
+        &self,
+        group_id: &str,
+        paused: bool,
+    ) -> AppResult<DuplicatedGroup> {
+        let source = self
+            .repository
+            .get_groups()?
+            .into_iter()
+            .find(|g| g.id == group_id)
+            .ok_or_else(|| AppError::NotFound(format!("Group with id {} not found", group_id)))?;
+
+        let group = source.duplicate();
+        let mut copies = Vec::new();
+        for reminder in self.repository.find_reminders_by_group(group_id)? {
+            if reminder.status != ReminderStatus::Deleted {
+                let copy = Self::copy_reminder(&reminder, group.id.clone(), paused)?;
+                copies.push((reminder, copy));
+            }
+        }
+
+        self.repository.add_group(group.clone())?;
+        let mut reminders = Vec::new();
+        for (source, mut reminder) in copies {
+            self.repository.add_reminder(reminder.clone())?;
+            self.copy_attachments(&source, &mut reminder)?;
+            self.sync_reminder_job(&reminder).await?;
+            reminders.push(reminder);
+        }
+        self.save_data().await?;
+        println!("Duplicated group {} as {}", group_id, group.id);
+        Ok(DuplicatedGroup { group, reminders })
+    }
+
     pub async fn get_reminders(&self) -> AppResult<Vec<Reminder>> {
         self.repository.get_reminders()
     }
 
-    pub async fn create_reminder(&self, request: CreateReminderRequest) -> AppResult<Reminder> {
-        let reminder = Reminder::new(
+    pub async fn create_reminder(
+        &self,
+        request: CreateReminderRequest,
+    ) -> AppResult<SavedReminder> {
+        let (schedule, windows) =
+            validation::validate_create_reminder(&request, &self.references()?)?;
+        let mut reminder = Reminder::new(
             request.title,
             request.color,
             request.group_id,
-            request.cron_expression,
+            schedule,
             request.description,
-            request.start_at,
-            request.end_at,
         );
+        if let Some(windows) = windows {
+            reminder.set_windows(windows)?;
+        }
+        if let Some(max_per_day) = request.max_per_day {
+            reminder.set_max_per_day(max_per_day);
+        }
+        if let Some(contexts) = request.contexts {
+            reminder.contexts = contexts;
+        }
+        if let Some(priority) = request.priority {
+            reminder.priority = priority;
+        }
+        if let Some(tags) = request.tags {
+            reminder.tags = tags;
+        }
+        for text in request.checklist.unwrap_or_default() {
+            reminder.add_checklist_item(&text)?;
+        }
 
         // 添加到仓库
         self.repository.add_reminder(reminder.clone())?;
@@ -86,110 +179,1417 @@ impl ReminderService {
 
         self.save_data().await?;
         println!("Created reminder: {:?}", reminder);
-        Ok(reminder)
+        let warnings = self.schedule_warnings(&reminder);
+        Ok(SavedReminder { reminder, warnings })
     }
 
     pub async fn update_reminder(
         &self,
         reminder_id: &str,
         request: UpdateReminderRequest,
-    ) -> AppResult<Reminder> {
+    ) -> AppResult<SavedReminder> {
         let mut reminder = self
             .repository
             .find_reminder(reminder_id)?
             .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
 
-        let schedule_changed = reminder.update(
-            request.title,
-            request.color,
-            request.cron_expression,
-            request.description,
-            request.start_at,
-            request.end_at,
-        );
+        let parsed = validation::validate_update_reminder(&request, &self.references()?)?;
+        let schedule_changed = reminder.update(request, parsed)?;
 
+        reminder.touch();
         self.repository.update_reminder(&reminder)?;
 
         // 如果调度信息发生变化，重新设置调度任务
         if schedule_changed {
-            let scheduler = self.scheduler.read().await;
-            // 先移除旧任务
-            if let Err(e) = scheduler.remove_reminder_job(&reminder.id) {
-                eprintln!("Failed to remove old reminder job: {}", e);
+            self.sync_reminder_job(&reminder).await?;
+        }
+
+        self.save_data().await?;
+        println!("Updated reminder: {:?}", reminder);
+        let warnings = self.schedule_warnings(&reminder);
+        Ok(SavedReminder { reminder, warnings })
+    }
+
+    /// 复制提醒到同一分组，paused 为 true 时副本创建为暂停状态
+    pub async fn duplicate_reminder(&self, reminder_id: &str, paused: bool) -> AppResult<Reminder> {
+        let source = self
+            .repository
+            .find_reminder(reminder_id)?
+            .filter(|reminder| reminder.status != ReminderStatus::Deleted)
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        let mut reminder = Self::copy_reminder(&source, source.group_id.clone(), paused)?;
+        self.repository.add_reminder(reminder.clone())?;
+        self.copy_attachments(&source, &mut reminder)?;
+        self.sync_reminder_job(&reminder).await?;
+        self.save_data().await?;
+        println!("Duplicated reminder {} as {}", reminder_id, reminder.id);
+        Ok(reminder)
+    }
+
+    /// 生成提醒副本，尚未加入仓库，附件在加入后由 copy_attachments 复制
+    fn copy_reminder(source: &Reminder, group_id: String, paused: bool) -> AppResult<Reminder> {
+        let mut reminder = source.duplicate(group_id)?;
+        if paused {
+            reminder.pause(None, false)?;
+        }
+        Ok(reminder)
+    }
+
+    /// 为已加入仓库的副本复制附件，复制失败的文件附件跳过
+    fn copy_attachments(&self, source: &Reminder, reminder: &mut Reminder) -> AppResult<()> {
+        if source.attachments.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp();
+        for attachment in &source.attachments {
+            let id = uuid::Uuid::new_v4().to_string();
+            let location = match attachment.kind {
+                AttachmentKind::Url => attachment.location.clone(),
+                AttachmentKind::File => {
+                    match self.attachments.duplicate_file(&reminder.id, &id, attachment) {
+                        Ok(target) => target.to_string_lossy().into_owned(),
+                        Err(e) => {
+                            eprintln!("Failed to copy attachment {}: {}", attachment.id, e);
+                            continue;
+                        }
+                    }
+                }
+            };
+            reminder.attachments.push(Attachment {
+                id,
+                location,
+                added_at: now,
+                ..attachment.clone()
+            });
+        }
+        self.repository.update_reminder(reminder)
+    }
+
+    pub async fn pause_reminder(
+        &self,
+        reminder_id: &str,
+        request: PauseReminderRequest,
+    ) -> AppResult<Reminder> {
+        let mut reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        let now = chrono::Utc::now().timestamp();
+        let paused_until = match (request.until, request.duration_minutes) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::Validation(
+                    "Only one of until and duration_minutes can be set".to_string(),
+                ))
+            }
+            (Some(until), None) => {
+                let until = parse_local_datetime(&until)
+                    .ok_or_else(|| AppError::Validation(format!("Invalid pause time: {}", until)))?
+                    .timestamp();
+                if until <= now {
+                    return Err(AppError::Validation("Pause time must be in the future".to_string()));
+                }
+                Some(until)
             }
-            drop(scheduler);
+            (None, Some(minutes)) => {
+                if minutes <= 0 {
+                    return Err(AppError::Validation(
+                        "Pause duration must be positive".to_string(),
+                    ));
+                }
+                Some(now + minutes * 60)
+            }
+            (None, None) => None,
+        };
+
+        reminder.pause(paused_until, request.notify_on_resume.unwrap_or(false))?;
+        reminder.touch();
+        self.repository.update_reminder(&reminder)?;
+        self.sync_reminder_job(&reminder).await?;
+        self.save_data().await?;
+        println!("Paused reminder: {} until {:?}", reminder_id, paused_until);
+        Ok(reminder)
+    }
+
+    /// 恢复所有暂停已到期的提醒，返回恢复的数量
+    pub async fn resume_expired_pauses(&self) -> AppResult<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let expired: Vec<Reminder> = self
+            .repository
+            .get_reminders()?
+            .into_iter()
+            .filter(|r| r.is_pause_expired(now))
+            .collect();
 
-            // 添加新任务
-            let mut scheduler = self.scheduler.write().await;
-            if let Err(e) = scheduler.add_reminder_job(&reminder).await {
-                eprintln!("Failed to add updated reminder job: {}", e);
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        for mut reminder in expired.iter().cloned() {
+            let notify = reminder.notify_on_resume;
+            reminder.resume()?;
+            self.repository.update_reminder(&reminder)?;
+            self.sync_reminder_job(&reminder).await?;
+
+            if notify {
+                let scheduler = self.scheduler.read().await;
+                if let Err(e) = scheduler.send_notification("提醒已恢复", &reminder.title) {
+                    eprintln!("Failed to send resume notification: {}", e);
+                }
             }
+            println!("Auto resumed reminder: {}", reminder.id);
         }
 
         self.save_data().await?;
-        println!("Updated reminder: {:?}", reminder);
-        Ok(reminder)
+        Ok(expired.len())
     }
 
-    pub async fn pause_reminder(&self, reminder_id: &str) -> AppResult<()> {
+    pub async fn resume_reminder(&self, reminder_id: &str) -> AppResult<()> {
+        self.transition_reminder(reminder_id, Reminder::resume).await?;
+        println!("Resumed reminder: {}", reminder_id);
+        Ok(())
+    }
+
+    pub async fn cancel_reminder(&self, reminder_id: &str) -> AppResult<()> {
+        self.transition_reminder(reminder_id, Reminder::cancel).await?;
+        println!("Cancelled reminder: {}", reminder_id);
+        Ok(())
+    }
+
+    pub async fn complete_reminder(&self, reminder_id: &str) -> AppResult<()> {
+        let reminder = Self::record_completion(self.repository.as_ref(), reminder_id)?;
+        self.sync_reminder_job(&reminder).await?;
+        self.save_data().await?;
+        self.scheduler.read().await.update_tray_flag();
+        println!("Completed reminder: {}", reminder_id);
+        Ok(())
+    }
+
+    /// 确认已看到通知，停止重复通知并清除托盘标记
+    pub async fn acknowledge_reminder(&self, reminder_id: &str) -> AppResult<Reminder> {
         let mut reminder = self
             .repository
             .find_reminder(reminder_id)?
             .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
 
-        reminder.pause();
+        reminder.acknowledge();
         self.repository.update_reminder(&reminder)?;
+        Self::record_occurrence_response(
+            self.repository.as_ref(),
+            reminder_id,
+            OccurrenceResponse::Acknowledged,
+        )?;
+        self.scheduler.read().await.update_tray_flag();
         self.save_data().await?;
-        println!("Paused reminder: {}", reminder_id);
+        Ok(reminder)
+    }
+
+    /// 记录提醒的一次完成并把最近一次触发标记为完成，返回更新后的提醒
+    fn record_completion(
+        repository: &dyn DataRepository,
+        reminder_id: &str,
+    ) -> AppResult<Reminder> {
+        let mut reminder = repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        reminder.record_completion()?;
+        reminder.touch();
+        repository.update_reminder(&reminder)?;
+        Self::record_occurrence_response(repository, reminder_id, OccurrenceResponse::Completed)?;
+        Ok(reminder)
+    }
+
+    /// 把用户响应记到该提醒最近一次已送达的触发上
+    fn record_occurrence_response(
+        repository: &dyn DataRepository,
+        reminder_id: &str,
+        response: OccurrenceResponse,
+    ) -> AppResult<()> {
+        let entry = repository
+            .get_occurrence_log()?
+            .into_iter()
+            .rev()
+            .find(|entry| entry.reminder_id == reminder_id && entry.is_delivered());
+        if let Some(mut entry) = entry {
+            if entry.respond(response, chrono::Utc::now().timestamp()) {
+                repository.update_occurrence_log_entry(&entry)?;
+            }
+        }
         Ok(())
     }
 
-    pub async fn resume_reminder(&self, reminder_id: &str) -> AppResult<()> {
+    /// 手动把某次触发标记为完成或错过，可覆盖之前的标记
+    pub async fn mark_occurrence(
+        &self,
+        entry_id: &str,
+        done: bool,
+    ) -> AppResult<OccurrenceLogEntry> {
+        let mut entry = self
+            .repository
+            .get_occurrence_log()?
+            .into_iter()
+            .find(|entry| entry.id == entry_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Occurrence log entry with id {} not found", entry_id))
+            })?;
+        if !entry.is_delivered() {
+            return Err(AppError::Validation(
+                "Only delivered occurrences can be marked".to_string(),
+            ));
+        }
+
+        entry.response = Some(if done {
+            OccurrenceResponse::Completed
+        } else {
+            OccurrenceResponse::Missed
+        });
+        entry.responded_at = Some(chrono::Utc::now().timestamp());
+        self.repository.update_occurrence_log_entry(&entry)?;
+        self.save_data().await?;
+        Ok(entry)
+    }
+
+    /// 每个提醒和分组的完成统计，已删除的提醒不计入
+    pub async fn get_stats(&self) -> AppResult<StatsReport> {
+        Self::stats_report(self.repository.as_ref(), chrono::Utc::now().timestamp())
+    }
+
+    fn stats_report(repository: &dyn DataRepository, now: i64) -> AppResult<StatsReport> {
+        let log = repository.get_occurrence_log()?;
+        let reminders: Vec<Reminder> = repository
+            .get_reminders()?
+            .into_iter()
+            .filter(|reminder| reminder.status != ReminderStatus::Deleted)
+            .collect();
+
+        let mut outcomes_by_reminder = HashMap::new();
+        for reminder in &reminders {
+            let entries: Vec<&OccurrenceLogEntry> =
+                log.iter().filter(|entry| entry.reminder_id == reminder.id).collect();
+            outcomes_by_reminder.insert(reminder.id.as_str(), AdherenceStats::outcomes(&entries));
+        }
+
+        let reminder_stats = reminders
+            .iter()
+            .map(|reminder| {
+                let outcomes = &outcomes_by_reminder[reminder.id.as_str()];
+                ReminderStats {
+                    reminder_id: reminder.id.clone(),
+                    title: reminder.title.clone(),
+                    group_id: reminder.group_id.clone(),
+                    stats: AdherenceStats::from_outcomes(outcomes, now),
+                }
+            })
+            .collect();
+
+        let group_stats = repository
+            .get_groups()?
+            .into_iter()
+            .map(|group| {
+                let mut outcomes: Vec<(i64, bool)> = reminders
+                    .iter()
+                    .filter(|reminder| reminder.group_id == group.id)
+                    .flat_map(|reminder| outcomes_by_reminder[reminder.id.as_str()].iter().copied())
+                    .collect();
+                outcomes.sort_by_key(|(at, _)| *at);
+                GroupStats {
+                    group_id: group.id,
+                    name: group.name,
+                    stats: AdherenceStats::from_outcomes(&outcomes, now),
+                }
+            })
+            .collect();
+
+        Ok(StatsReport {
+            generated_at: now,
+            reminders: reminder_stats,
+            groups: group_stats,
+        })
+    }
+
+    /// 按提醒和计划触发时间范围查询触发记录，按时间先后排列
+    pub async fn get_occurrence_log(
+        &self,
+        reminder_id: Option<&str>,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> AppResult<Vec<OccurrenceLogEntry>> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if to < from {
+                return Err(AppError::Validation(
+                    "Range end must not be before its start".to_string(),
+                ));
+            }
+        }
+
+        let mut entries: Vec<OccurrenceLogEntry> = self
+            .repository
+            .get_occurrence_log()?
+            .into_iter()
+            .filter(|entry| reminder_id.is_none_or(|id| entry.reminder_id == id))
+            .filter(|entry| entry.in_range(from, to))
+            .collect();
+        entries.sort_by_key(|entry| (entry.scheduled_at, entry.fired_at));
+        Ok(entries)
+    }
+
+    pub async fn set_history_retention(
+        &self,
+        retention: HistoryRetention,
+    ) -> AppResult<AppSettings> {
+        retention.validate()?;
+
+        let mut settings = self.repository.get_settings()?;
+        settings.history_retention = retention;
+        self.repository.update_settings(&settings)?;
+        self.prune_occurrence_log_with(retention)?;
+        self.save_data().await?;
+        Ok(settings)
+    }
+
+    /// 按保留策略清理触发记录，返回清理的条数
+    pub async fn prune_occurrence_log(&self) -> AppResult<usize> {
+        let retention = self.repository.get_settings()?.history_retention;
+        let removed = self.prune_occurrence_log_with(retention)?;
+        if removed > 0 {
+            self.save_data().await?;
+        }
+        Ok(removed)
+    }
+
+    fn prune_occurrence_log_with(&self, retention: HistoryRetention) -> AppResult<usize> {
+        let mut log = self.repository.get_occurrence_log()?;
+        let removed = retention.apply(&mut log, chrono::Utc::now().timestamp());
+        if removed > 0 {
+            self.repository.replace_occurrence_log(log)?;
+        }
+        Ok(removed)
+    }
+
+    /// 保存调度线程产生的修改（触发记录、last_triggered 等）
+    pub async fn save_unsaved_changes(&self) -> AppResult<bool> {
+        if !self.repository.take_unsaved() {
+            return Ok(false);
+        }
+        self.save_data().await?;
+        Ok(true)
+    }
+
+    pub async fn add_checklist_item(&self, reminder_id: &str, text: &str) -> AppResult<Reminder> {
+        self.modify_details(reminder_id, |reminder| reminder.add_checklist_item(text))
+            .await
+    }
+
+    pub async fn toggle_checklist_item(
+        &self,
+        reminder_id: &str,
+        item_id: &str,
+    ) -> AppResult<Reminder> {
+        self.modify_details(reminder_id, |reminder| reminder.toggle_checklist_item(item_id))
+            .await
+    }
+
+    pub async fn reorder_checklist(
+        &self,
+        reminder_id: &str,
+        item_ids: &[String],
+    ) -> AppResult<Reminder> {
+        self.modify_details(reminder_id, |reminder| reminder.reorder_checklist(item_ids))
+            .await
+    }
+
+    pub async fn remove_checklist_item(
+        &self,
+        reminder_id: &str,
+        item_id: &str,
+    ) -> AppResult<Reminder> {
+        self.modify_details(reminder_id, |reminder| reminder.remove_checklist_item(item_id))
+            .await
+    }
+
+    pub async fn add_url_attachment(
+        &self,
+        reminder_id: &str,
+        url: &str,
+        name: Option<String>,
+    ) -> AppResult<Reminder> {
+        let attachment = Attachment::url(url, name, chrono::Utc::now().timestamp())?;
+        self.modify_details(reminder_id, |reminder| {
+            reminder.attachments.push(attachment);
+            Ok(())
+        })
+        .await
+    }
+
+    /// 把本地文件复制到应用数据目录后作为附件
+    pub async fn add_file_attachment(
+        &self,
+        reminder_id: &str,
+        path: &str,
+        name: Option<String>,
+    ) -> AppResult<Reminder> {
+        if self.repository.find_reminder(reminder_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Reminder with id {} not found", reminder_id)));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let source = std::path::Path::new(path);
+        let target = self.attachments.import_file(reminder_id, &id, source)?;
+        let file_name = source
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string());
+        let attachment = Attachment::file(
+            id,
+            Attachment::display_name(name, &file_name),
+            target.to_string_lossy().into_owned(),
+            chrono::Utc::now().timestamp(),
+        );
+        self.modify_details(reminder_id, |reminder| {
+            reminder.attachments.push(attachment);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn remove_attachment(
+        &self,
+        reminder_id: &str,
+        attachment_id: &str,
+    ) -> AppResult<Reminder> {
+        let mut removed = None;
+        let reminder = self
+            .modify_details(reminder_id, |reminder| {
+                removed = Some(reminder.remove_attachment(attachment_id)?);
+                Ok(())
+            })
+            .await?;
+        if let Some(attachment) = removed {
+            self.attachments.remove_file(&attachment);
+        }
+        Ok(reminder)
+    }
+
+    pub async fn open_attachment(&self, reminder_id: &str, attachment_id: &str) -> AppResult<()> {
+        let reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+        self.attachments.open(reminder.find_attachment(attachment_id)?)
+    }
+
+    /// 修改清单或附件不影响调度，只需更新并保存
+    async fn modify_details(
+        &self,
+        reminder_id: &str,
+        modify: impl FnOnce(&mut Reminder) -> AppResult<()>,
+    ) -> AppResult<Reminder> {
         let mut reminder = self
             .repository
             .find_reminder(reminder_id)?
             .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
 
-        reminder.resume();
+        modify(&mut reminder)?;
+        reminder.touch();
         self.repository.update_reminder(&reminder)?;
         self.save_data().await?;
-        println!("Resumed reminder: {}", reminder_id);
+        Ok(reminder)
+    }
+
+    /// 按条件筛选并排序提醒
+    pub async fn query_reminders(&self, query: ReminderQuery) -> AppResult<Vec<Reminder>> {
+        let mut reminders: Vec<Reminder> = self
+            .repository
+            .get_reminders()?
+            .into_iter()
+            .filter(|reminder| query.matches(reminder))
+            .collect();
+        query.sort(&mut reminders);
+        Ok(reminders)
+    }
+
+    pub async fn delete_reminder(&self, reminder_id: &str) -> AppResult<()> {
+        self.transition_reminder(reminder_id, Reminder::delete).await?;
+        println!("Deleted reminder: {}", reminder_id);
         Ok(())
     }
 
-    pub async fn cancel_reminder(&self, reminder_id: &str) -> AppResult<()> {
+    /// 执行状态迁移，并同步调度任务与持久化数据
+    async fn transition_reminder(
+        &self,
+        reminder_id: &str,
+        transition: fn(&mut Reminder) -> AppResult<()>,
+    ) -> AppResult<Reminder> {
         let mut reminder = self
             .repository
             .find_reminder(reminder_id)?
             .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
 
-        reminder.cancel();
+        transition(&mut reminder)?;
+        reminder.touch();
         self.repository.update_reminder(&reminder)?;
+        self.sync_reminder_job(&reminder).await?;
         self.save_data().await?;
-        println!("Cancelled reminder: {}", reminder_id);
+        Ok(reminder)
+    }
+
+    /// 按提醒当前状态注册或注销调度任务：只有活跃的提醒持有任务
+    async fn sync_reminder_job(&self, reminder: &Reminder) -> AppResult<()> {
+        let mut scheduler = self.scheduler.write().await;
+        scheduler.remove_reminder_job(&reminder.id)?;
+
+        if reminder.is_active() {
+            if let Err(e) = scheduler.add_reminder_job(reminder).await {
+                eprintln!("Failed to add reminder job {}: {}", reminder.id, e);
+            }
+        }
         Ok(())
     }
 
-    pub async fn delete_reminder(&self, reminder_id: &str) -> AppResult<()> {
+    /// 跳过下一次触发
+    pub async fn skip_next_occurrence(&self, reminder_id: &str) -> AppResult<Reminder> {
         let mut reminder = self
             .repository
             .find_reminder(reminder_id)?
             .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
 
-        reminder.delete();
+        let schedule = Self::recurring_schedule(&reminder)?;
+        let occurrence_at = Self::next_free_occurrence(&reminder, &schedule)?;
+
+        reminder.add_occurrence_override(OccurrenceOverride::Skip { occurrence_at });
+        reminder.touch();
         self.repository.update_reminder(&reminder)?;
+        self.save_data().await?;
+        println!("Skipped occurrence of reminder {} at {}", reminder_id, occurrence_at);
+        Ok(reminder)
+    }
 
-        // 移除调度任务
-        let scheduler = self.scheduler.read().await;
-        if let Err(e) = scheduler.remove_reminder_job(reminder_id) {
-            eprintln!("Failed to remove reminder job {}: {}", reminder_id, e);
+    /// 跳过某一天（本地日期，`YYYY-MM-DD`）的所有触发
+    pub async fn skip_occurrence_date(
+        &self,
+        reminder_id: &str,
+        date: &str,
+    ) -> AppResult<Reminder> {
+        let mut reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        Self::recurring_schedule(&reminder)?;
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| AppError::Validation(format!("Invalid date: {}", date)))?;
+        if date < chrono::Local::now().date_naive() {
+            return Err(AppError::Validation("Skipped date must not be in the past".to_string()));
         }
 
+        reminder.add_occurrence_override(OccurrenceOverride::SkipDate { date });
+        reminder.touch();
+        self.repository.update_reminder(&reminder)?;
         self.save_data().await?;
-        println!("Deleted reminder: {}", reminder_id);
+        println!("Skipped reminder {} on {}", reminder_id, date);
+        Ok(reminder)
+    }
+
+    /// 把一次触发改到其他时间，未指定 occurrence_at 时改期下一次触发
+    pub async fn move_occurrence(
+        &self,
+        reminder_id: &str,
+        occurrence_at: Option<i64>,
+        new_time: &str,
+    ) -> AppResult<Reminder> {
+        let mut reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        let schedule = Self::recurring_schedule(&reminder)?;
+        let now = chrono::Utc::now().timestamp();
+
+        let occurrence_at = match occurrence_at {
+            Some(at) => {
+                let is_occurrence = chrono::Utc
+                    .timestamp_opt(at, 0)
+                    .single()
+                    .is_some_and(|time| schedule.matches(time, &Local));
+                if !is_occurrence || at <= now {
+                    return Err(AppError::Validation(format!(
+                        "{} is not an upcoming occurrence",
+                        at
+                    )));
+                }
+                at
+            }
+            None => Self::next_free_occurrence(&reminder, &schedule)?,
+        };
+
+        let new_time = parse_local_datetime(new_time)
+            .ok_or_else(|| AppError::Validation(format!("Invalid time: {}", new_time)))?
+            .timestamp();
+        if new_time <= now {
+            return Err(AppError::Validation("New time must be in the future".to_string()));
+        }
+
+        // 同一次触发只保留最新的调整
+        reminder.occurrence_overrides.retain(|o| {
+            matches!(o, OccurrenceOverride::SkipDate { .. }) || !o.applies_to(occurrence_at)
+        });
+        reminder.add_occurrence_override(OccurrenceOverride::Move {
+            occurrence_at,
+            new_time,
+        });
+        reminder.touch();
+        self.repository.update_reminder(&reminder)?;
+        self.save_data().await?;
+        println!(
+            "Moved occurrence of reminder {} from {} to {}",
+            reminder_id, occurrence_at, new_time
+        );
+        Ok(reminder)
+    }
+
+    /// 清除所有单次调整
+    pub async fn clear_occurrence_overrides(&self, reminder_id: &str) -> AppResult<Reminder> {
+        let mut reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+
+        reminder.occurrence_overrides.clear();
+        reminder.touch();
+        self.repository.update_reminder(&reminder)?;
+        self.save_data().await?;
+        println!("Cleared occurrence overrides of reminder {}", reminder_id);
+        Ok(reminder)
+    }
+
+    /// 单次调整只适用于按 cron 重复的提醒
+    fn recurring_schedule(reminder: &Reminder) -> AppResult<CronExpr> {
+        let Schedule::Cron { expression } = &reminder.schedule else {
+            return Err(AppError::Validation(
+                "Only recurring reminders support occurrence overrides".to_string(),
+            ));
+        };
+        parse_schedule(expression)
+    }
+
+    /// 下一次尚未被调整的触发时间
+    fn next_free_occurrence(reminder: &Reminder, schedule: &CronExpr) -> AppResult<i64> {
+        schedule
+            .upcoming(chrono::Utc::now(), &Local)
+            .map(|time| time.timestamp())
+            .take(1000)
+            .find(|at| reminder.override_for(*at).is_none())
+            .ok_or_else(|| AppError::Validation("Reminder has no upcoming occurrence".to_string()))
+    }
+
+    /// 把所有活跃提醒展开为 [from, to] 范围内的具体触发，供日历和日程视图使用
+    pub async fn get_occurrences(&self, from: i64, to: i64) -> AppResult<OccurrenceList> {
+        if to < from {
+            return Err(AppError::Validation(
+                "Range end must not be before its start".to_string(),
+            ));
+        }
+        if to - from > MAX_OCCURRENCE_RANGE_SECONDS {
+            return Err(AppError::Validation("Range must not exceed one year".to_string()));
+        }
+
+        let mut occurrences = Vec::new();
+        let mut truncated = false;
+        for reminder in self.repository.get_reminders()? {
+            let (times, reminder_truncated) =
+                match Self::expand_occurrences(&reminder, from, to) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        eprintln!("Failed to expand occurrences of {}: {}", reminder.id, e);
+                        continue;
+                    }
+                };
+            truncated |= reminder_truncated;
+            occurrences.extend(times.into_iter().map(|(at, moved)| Occurrence {
+                reminder_id: reminder.id.clone(),
+                title: reminder.title.clone(),
+                color: reminder.color.clone(),
+                at,
+                moved,
+            }));
+        }
+
+        occurrences.sort_by_key(|occurrence| occurrence.at);
+        if occurrences.len() > MAX_OCCURRENCES {
+            occurrences.truncate(MAX_OCCURRENCES);
+            truncated = true;
+        }
+        Ok(OccurrenceList {
+            occurrences,
+            truncated,
+        })
+    }
+
+    /// 展开单个提醒的触发时间，已考虑时间窗口、暂停和单次调整。
+    /// 返回 (时间, 是否改期) 列表及是否被截断
+    fn expand_occurrences(
+        reminder: &Reminder,
+        from: i64,
+        to: i64,
+    ) -> AppResult<(Vec<(i64, bool)>, bool)> {
+        // 只展开活跃提醒，以及到期后会自动恢复的暂停提醒
+        let visible_from = match reminder.status {
+            ReminderStatus::Active => from,
+            ReminderStatus::Paused => match reminder.paused_until {
+                Some(until) => from.max(until),
+                None => return Ok((Vec::new(), false)),
+            },
+            _ => return Ok((Vec::new(), false)),
+        };
+        let in_range = |at: i64| at >= visible_from && at <= to;
+
+        let mut times = Vec::new();
+        let mut truncated = false;
+        // 重复触发的计划先生成候选时间，再按窗口、单次调整和每日上限过滤
+        let candidates: Box<dyn Iterator<Item = i64>> = match &reminder.schedule {
+            Schedule::Cron { .. } => {
+                let schedule = schedule_for(reminder)?;
+                let start = chrono::Utc
+                    .timestamp_opt(visible_from - 1, 0)
+                    .single()
+                    .ok_or_else(|| AppError::Validation("Invalid range start".to_string()))?;
+                let upcoming: Vec<i64> = schedule
+                    .upcoming(start, &Local)
+                    .map(|time| time.timestamp())
+                    .take_while(|at| *at <= to)
+                    .take(MAX_OCCURRENCES + 1)
+                    .collect();
+                Box::new(upcoming.into_iter())
+            }
+            Schedule::Interval { minutes } => {
+                let step = i64::from((*minutes).max(1)) * 60;
+                let anchor = reminder.last_triggered.unwrap_or(reminder.created_at);
+                let skipped_steps = ((visible_from - anchor).max(0) + step - 1) / step;
+                let skipped_steps = skipped_steps.max(1);
+                let first = anchor + skipped_steps * step;
+                Box::new((0..).map(move |n| first + n * step).take_while(move |at| *at <= to))
+            }
+            Schedule::Once { at } => {
+                if in_range(*at) && reminder.last_triggered.is_none_or(|last| last < *at) {
+                    times.push((*at, false));
+                }
+                Box::new(std::iter::empty())
+            }
+            Schedule::AfterCompletion { .. } => {
+                if let Some(next_due_at) = reminder.next_due_at.filter(|at| in_range(*at)) {
+                    times.push((next_due_at, false));
+                }
+                Box::new(std::iter::empty())
+            }
+            Schedule::Deadline {
+                due_at,
+                alert_minutes_before,
+                ..
+            } => {
+                for minutes in alert_minutes_before {
+                    let at = due_at - i64::from(*minutes) * 60;
+                    if in_range(at) {
+                        times.push((at, false));
+                    }
+                }
+                Box::new(std::iter::empty())
+            }
+            Schedule::Unscheduled => Box::new(std::iter::empty()),
+        };
+
+        let mut fires_by_day: HashMap<NaiveDate, u32> = HashMap::new();
+        for (scanned, at) in candidates.enumerate() {
+            if scanned >= MAX_OCCURRENCES {
+                truncated = true;
+                break;
+            }
+            if reminder.override_for(at).is_some() {
+                continue;
+            }
+            let Some(local) = Local.timestamp_opt(at, 0).single().map(|t| t.naive_local()) else {
+                continue;
+            };
+            let local_minute = local.with_second(0).unwrap_or(local);
+            if reminder.window_skip_reason(local_minute).is_some() {
+                continue;
+            }
+            // 当天已达上限的触发不会通知
+            if let Some(max_per_day) = reminder.max_per_day {
+                let date = local.date();
+                let fires = fires_by_day
+                    .entry(date)
+                    .or_insert_with(|| reminder.fires_on(date));
+                if *fires >= max_per_day {
+                    continue;
+                }
+                *fires += 1;
+            }
+            times.push((at, false));
+        }
+
+        for occurrence_override in &reminder.occurrence_overrides {
+            if let OccurrenceOverride::Move { new_time, .. } = occurrence_override {
+                if in_range(*new_time) {
+                    times.push((*new_time, true));
+                }
+            }
+        }
+        Ok((times, truncated))
+    }
+
+    /// 检查提醒与其他提醒之间的调度冲突；提醒已保存，检查失败时只记录日志
+    fn schedule_warnings(&self, reminder: &Reminder) -> Vec<ScheduleWarning> {
+        self.find_schedule_conflicts(reminder).unwrap_or_else(|e| {
+            eprintln!("Failed to check schedule conflicts of {}: {}", reminder.id, e);
+            Vec::new()
+        })
+    }
+
+    fn find_schedule_conflicts(&self, reminder: &Reminder) -> AppResult<Vec<ScheduleWarning>> {
+        let now = chrono::Utc::now().timestamp();
+        let until = now + CONFLICT_LOOKAHEAD_SECONDS;
+        let reminders = self.repository.get_reminders()?;
+
+        let (own_times, _) = Self::expand_occurrences(reminder, now, until)?;
+        let own_minutes: HashSet<i64> = own_times.iter().map(|(at, _)| at / 60).collect();
+        let own_hours: HashSet<i64> = own_times.iter().map(|(at, _)| at / 3600).collect();
+
+        let mut reminders_by_minute: HashMap<i64, Vec<&str>> = HashMap::new();
+        let mut fires_by_hour: HashMap<i64, usize> = HashMap::new();
+        for other in &reminders {
+            let times = if other.id == reminder.id {
+                own_times.clone()
+            } else {
+                Self::expand_occurrences(other, now, until)
+                    .map(|(times, _)| times)
+                    .unwrap_or_default()
+            };
+            for (at, _) in times {
+                if own_minutes.contains(&(at / 60)) {
+                    let ids = reminders_by_minute.entry(at / 60).or_default();
+                    if !ids.contains(&other.id.as_str()) {
+                        ids.push(other.id.as_str());
+                    }
+                }
+                *fires_by_hour.entry(at / 3600).or_default() += 1;
+            }
+        }
+
+        let mut warnings = Vec::new();
+
+        // 只报告最早的一个拥挤时刻，避免每天重复提示
+        if let Some((minute, ids)) = reminders_by_minute
+            .iter()
+            .filter(|(_, ids)| ids.len() > MAX_REMINDERS_PER_MINUTE)
+            .min_by_key(|(minute, _)| **minute)
+        {
+            warnings.push(ScheduleWarning::CrowdedMinute {
+                at: minute * 60,
+                reminder_ids: ids.iter().map(|id| id.to_string()).collect(),
+            });
+        }
+
+        if let Some((hour, fires)) = fires_by_hour
+            .iter()
+            .filter(|(hour, fires)| own_hours.contains(hour) && **fires > MAX_FIRES_PER_HOUR)
+            .min_by_key(|(hour, _)| **hour)
+        {
+            warnings.push(ScheduleWarning::HighFireRate {
+                hour_start: hour * 3600,
+                fires: *fires,
+                limit: MAX_FIRES_PER_HOUR,
+            });
+        }
+
+        if !reminder.windows.is_empty() {
+            for other in &reminders {
+                if other.id == reminder.id
+                    || other.group_id != reminder.group_id
+                    || !matches!(other.status, ReminderStatus::Active | ReminderStatus::Paused)
+                {
+                    continue;
+                }
+                let overlapping = reminder
+                    .windows
+                    .iter()
+                    .any(|window| other.windows.iter().any(|other| window.overlaps(other)));
+                if overlapping {
+                    warnings.push(ScheduleWarning::OverlappingWindow {
+                        reminder_id: other.id.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    pub async fn get_routines(&self) -> AppResult<Vec<Routine>> {
+        self.repository.get_routines()
+    }
+
+    pub async fn create_routine(&self, request: CreateRoutineRequest) -> AppResult<Routine> {
+        if let Some(cron_expression) = &request.cron_expression {
+            if !cron_expression.trim().is_empty() {
+                parse_schedule(cron_expression)?;
+            }
+        }
+        let routine = Routine::new(request)?;
+        self.repository.add_routine(routine.clone())?;
+        self.save_data().await?;
+        println!("Created routine: {}", routine.name);
+        Ok(routine)
+    }
+
+    pub async fn update_routine(
+        &self,
+        routine_id: &str,
+        request: UpdateRoutineRequest,
+    ) -> AppResult<Routine> {
+        if let Some(cron_expression) = &request.cron_expression {
+            if !cron_expression.trim().is_empty() {
+                parse_schedule(cron_expression)?;
+            }
+        }
+        self.modify_routine(routine_id, |routine| {
+            routine.update(request)?;
+            routine.touch();
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn delete_routine(&self, routine_id: &str) -> AppResult<()> {
+        self.repository.remove_routine(routine_id)?;
+        self.save_data().await?;
+        println!("Deleted routine: {}", routine_id);
         Ok(())
     }
 
+    /// 手动开始例程，第一步按偏移时间触发
+    pub async fn start_routine(&self, routine_id: &str) -> AppResult<Routine> {
+        let now = chrono::Utc::now().timestamp();
+        self.modify_routine(routine_id, |routine| routine.start(now)).await
+    }
+
+    pub async fn stop_routine(&self, routine_id: &str) -> AppResult<Routine> {
+        self.modify_routine(routine_id, |routine| {
+            routine.stop();
+            Ok(())
+        })
+        .await
+    }
+
+    /// 确认当前步骤，下一步从现在开始计时
+    pub async fn acknowledge_routine_step(&self, routine_id: &str) -> AppResult<Routine> {
+        let now = chrono::Utc::now().timestamp();
+        self.modify_routine(routine_id, |routine| routine.acknowledge_step(now)).await
+    }
+
+    async fn modify_routine(
+        &self,
+        routine_id: &str,
+        modify: impl FnOnce(&mut Routine) -> AppResult<()>,
+    ) -> AppResult<Routine> {
+        let mut routine = self
+            .repository
+            .find_routine(routine_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Routine with id {} not found", routine_id)))?;
+
+        modify(&mut routine)?;
+        self.repository.update_routine(&routine)?;
+        self.save_data().await?;
+        Ok(routine)
+    }
+
+    pub async fn get_contexts(&self) -> AppResult<Vec<Context>> {
+        self.repository.get_contexts()
+    }
+
+    pub async fn create_context(&self, name: String) -> AppResult<Context> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::Validation("Context name cannot be empty".to_string()));
+        }
+        if self.repository.get_contexts()?.iter().any(|c| c.name == name) {
+            return Err(AppError::Validation(format!("Context {} already exists", name)));
+        }
+
+        let context = Context::new(name);
+        self.repository.add_context(context.clone())?;
+        self.save_data().await?;
+        println!("Created context: {}", context.name);
+        Ok(context)
+    }
+
+    /// 删除情境，同时从提醒和分组中移除；删除当前情境时取消过滤
+    pub async fn delete_context(&self, context_id: &str) -> AppResult<()> {
+        self.repository.remove_context(context_id)?;
+
+        for mut group in self.repository.get_groups()? {
+            if group.contexts.iter().any(|id| id == context_id) {
+                group.contexts.retain(|id| id != context_id);
+                group.touch();
+                self.repository.update_group(&group)?;
+            }
+        }
+        for mut reminder in self.repository.get_reminders()? {
+            if reminder.contexts.iter().any(|id| id == context_id) {
+                reminder.contexts.retain(|id| id != context_id);
+                reminder.touch();
+                self.repository.update_reminder(&reminder)?;
+            }
+        }
+
+        let mut settings = self.repository.get_settings()?;
+        if settings.active_context_id.as_deref() == Some(context_id) {
+            settings.active_context_id = None;
+            self.repository.update_settings(&settings)?;
+        }
+
+        self.save_data().await?;
+        println!("Deleted context: {}", context_id);
+        Ok(())
+    }
+
+    pub async fn set_group_contexts(
+        &self,
+        group_id: &str,
+        contexts: Vec<String>,
+    ) -> AppResult<ReminderGroup> {
+        let mut group = self
+            .repository
+            .get_groups()?
+            .into_iter()
+            .find(|g| g.id == group_id)
+            .ok_or_else(|| AppError::NotFound(format!("Group with id {} not found", group_id)))?;
+
+        self.validate_contexts(&contexts)?;
+        group.contexts = contexts;
+        group.touch();
+        self.repository.update_group(&group)?;
+        self.save_data().await?;
+        Ok(group)
+    }
+
+    pub async fn get_settings(&self) -> AppResult<AppSettings> {
+        self.repository.get_settings()
+    }
+
+    /// 切换当前情境，为空表示所有提醒都会通知
+    pub async fn set_active_context(&self, context_id: Option<String>) -> AppResult<AppSettings> {
+        if let Some(context_id) = &context_id {
+            self.validate_contexts(std::slice::from_ref(context_id))?;
+        }
+
+        let mut settings = self.repository.get_settings()?;
+        settings.active_context_id = context_id;
+        self.repository.update_settings(&settings)?;
+        self.save_data().await?;
+        println!("Active context: {:?}", settings.active_context_id);
+        Ok(settings)
+    }
+
+    pub async fn get_tags(&self) -> AppResult<Vec<Tag>> {
+        self.repository.get_tags()
+    }
+
+    pub async fn create_tag(&self, name: &str) -> AppResult<Tag> {
+        let name = self.available_tag_name(name, None)?;
+        let tag = Tag::new(name);
+        self.repository.add_tag(tag.clone())?;
+        self.save_data().await?;
+        println!("Created tag: {}", tag.name);
+        Ok(tag)
+    }
+
+    pub async fn rename_tag(&self, tag_id: &str, name: &str) -> AppResult<Tag> {
+        let mut tag = self
+            .repository
+            .find_tag(tag_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Tag with id {} not found", tag_id)))?;
+
+        tag.name = self.available_tag_name(name, Some(tag_id))?;
+        tag.touch();
+        self.repository.update_tag(&tag)?;
+        self.save_data().await?;
+        Ok(tag)
+    }
+
+    /// 把 source 标签合并到 target：提醒改为带 target 标签，然后删除 source
+    pub async fn merge_tags(&self, source_id: &str, target_id: &str) -> AppResult<Tag> {
+        if source_id == target_id {
+            return Err(AppError::Validation("Cannot merge a tag into itself".to_string()));
+        }
+        let target = self
+            .repository
+            .find_tag(target_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Tag with id {} not found", target_id)))?;
+
+        for mut reminder in self.repository.find_reminders_by_tag(source_id)? {
+            reminder.tags.retain(|tag| tag != source_id);
+            if !reminder.tags.iter().any(|tag| tag == target_id) {
+                reminder.tags.push(target_id.to_string());
+            }
+            reminder.touch();
+            self.repository.update_reminder(&reminder)?;
+        }
+        self.repository.remove_tag(source_id)?;
+        self.save_data().await?;
+        println!("Merged tag {} into {}", source_id, target.name);
+        Ok(target)
+    }
+
+    /// 删除标签并从所有提醒中移除
+    pub async fn delete_tag(&self, tag_id: &str) -> AppResult<()> {
+        self.repository.remove_tag(tag_id)?;
+        for mut reminder in self.repository.find_reminders_by_tag(tag_id)? {
+            reminder.tags.retain(|tag| tag != tag_id);
+            reminder.touch();
+            self.repository.update_reminder(&reminder)?;
+        }
+        self.save_data().await?;
+        println!("Deleted tag: {}", tag_id);
+        Ok(())
+    }
+
+    /// 对带有标签的所有提醒执行同一操作，逐个记录成功与失败
+    pub async fn apply_tag_action(&self, tag_id: &str, action: TagAction) -> AppResult<BulkResult> {
+        if self.repository.find_tag(tag_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Tag with id {} not found", tag_id)));
+        }
+
+        let mut result = BulkResult::default();
+        for reminder in self.repository.find_reminders_by_tag(tag_id)? {
+            if reminder.status == ReminderStatus::Deleted {
+                continue;
+            }
+            let outcome = match &action {
+                TagAction::Pause {
+                    until,
+                    duration_minutes,
+                } => {
+                    let request = PauseReminderRequest {
+                        until: until.clone(),
+                        duration_minutes: *duration_minutes,
+                        notify_on_resume: None,
+                    };
+                    self.pause_reminder(&reminder.id, request).await.map(|_| ())
+                }
+                TagAction::Resume => self.resume_reminder(&reminder.id).await,
+                TagAction::Cancel => self.cancel_reminder(&reminder.id).await,
+                TagAction::Complete => self.complete_reminder(&reminder.id).await,
+                TagAction::Delete => self.delete_reminder(&reminder.id).await,
+            };
+            match outcome {
+                Ok(()) => result.updated.push(reminder.id),
+                Err(e) => result.failed.push(BulkFailure {
+                    reminder_id: reminder.id,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        println!(
+            "Applied {:?} to tag {}: {} updated, {} failed",
+            action,
+            tag_id,
+            result.updated.len(),
+            result.failed.len()
+        );
+        Ok(result)
+    }
+
+    /// 校验标签名且不与其他标签重名（不区分大小写）
+    fn available_tag_name(&self, name: &str, except_id: Option<&str>) -> AppResult<String> {
+        let name = Tag::normalize_name(name);
+        if name.is_empty() {
+            return Err(AppError::Validation("Tag name cannot be empty".to_string()));
+        }
+        let taken = self
+            .repository
+            .get_tags()?
+            .iter()
+            .any(|t| Some(t.id.as_str()) != except_id && t.name.eq_ignore_ascii_case(&name));
+        if taken {
+            return Err(AppError::Validation(format!("Tag {} already exists", name)));
+        }
+        Ok(name)
+    }
+
+    /// 设置免打扰时段，为空表示关闭
+    pub async fn set_quiet_hours(&self, quiet_hours: Option<QuietHours>) -> AppResult<AppSettings> {
+        if let Some(quiet_hours) = &quiet_hours {
+            quiet_hours.validate()?;
+        }
+
+        let mut settings = self.repository.get_settings()?;
+        settings.quiet_hours = quiet_hours;
+        self.repository.update_settings(&settings)?;
+        self.save_data().await?;
+        Ok(settings)
+    }
+
+    /// 开启假期模式，until 为空时需手动关闭；已开启时只修改结束时间
+    pub async fn start_vacation(&self, until: Option<String>) -> AppResult<AppSettings> {
+        let now = chrono::Utc::now().timestamp();
+        let until = until
+            .map(|until| {
+                parse_local_datetime(&until)
+                    .map(|time| time.timestamp())
+                    .ok_or_else(|| AppError::Validation(format!("Invalid vacation end: {}", until)))
+            })
+            .transpose()?;
+        if until.is_some_and(|until| until <= now) {
+            return Err(AppError::Validation(
+                "Vacation end must be in the future".to_string(),
+            ));
+        }
+
+        let mut settings = self.repository.get_settings()?;
+        match settings.vacation.as_mut() {
+            Some(vacation) => vacation.until = until,
+            None => settings.vacation = Some(VacationMode::new(now, until)),
+        }
+        self.repository.update_settings(&settings)?;
+        self.save_data().await?;
+        println!("Vacation mode on until {:?}", until);
+        Ok(settings)
+    }
+
+    /// 关闭假期模式，返回待处理的汇总
+    pub async fn end_vacation(&self) -> AppResult<Option<VacationSummary>> {
+        let mut settings = self.repository.get_settings()?;
+        let Some(vacation) = settings.vacation.take() else {
+            return Ok(settings.vacation_summary);
+        };
+
+        // 错过触发的提醒先暂停，等待用户逐个恢复或忽略
+        let now = chrono::Utc::now().timestamp();
+        let mut held = Vec::new();
+        for missed in vacation.missed {
+            let Some(mut reminder) = self.repository.find_reminder(&missed.reminder_id)? else {
+                continue;
+            };
+            if !reminder.is_active() {
+                continue;
+            }
+            reminder.pause(None, false)?;
+            reminder.touch();
+            self.repository.update_reminder(&reminder)?;
+            self.sync_reminder_job(&reminder).await?;
+            held.push(missed);
+        }
+
+        if !held.is_empty() {
+            let summary = settings.vacation_summary.get_or_insert(VacationSummary {
+                started_at: vacation.started_at,
+                ended_at: now,
+                reminders: Vec::new(),
+            });
+            summary.started_at = summary.started_at.min(vacation.started_at);
+            summary.ended_at = now;
+            summary.reminders.extend(held);
+        }
+
+        self.repository.update_settings(&settings)?;
+        self.save_data().await?;
+        println!("Vacation mode off");
+        Ok(settings.vacation_summary)
+    }
+
+    /// 到达结束时间后自动关闭假期模式，并提示用户查看汇总
+    pub async fn end_expired_vacation(&self) -> AppResult<bool> {
+        let now = chrono::Utc::now().timestamp();
+        let settings = self.repository.get_settings()?;
+        if settings.vacation.is_none() || settings.is_on_vacation(now) {
+            return Ok(false);
+        }
+
+        if let Some(summary) = self.end_vacation().await? {
+            let body = format!("假期期间有 {} 个提醒错过了通知", summary.reminders.len());
+            let scheduler = self.scheduler.read().await;
+            if let Err(e) = scheduler.send_notification("假期已结束", &body) {
+                eprintln!("Failed to send vacation notification: {}", e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 从假期汇总中恢复提醒，已过期的完成间隔从现在重新计时
+    pub async fn reactivate_after_vacation(&self, reminder_id: &str) -> AppResult<Reminder> {
+        self.take_vacation_item(reminder_id)?;
+
+        let mut reminder = self
+            .repository
+            .find_reminder(reminder_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Reminder with id {} not found", reminder_id)))?;
+        if reminder.status == ReminderStatus::Paused {
+            reminder.resume()?;
+        }
+        if let Schedule::AfterCompletion { .. } = reminder.schedule {
+            let now = chrono::Utc::now().timestamp();
+            if reminder.next_due_at.is_none_or(|due| due <= now) {
+                reminder.set_schedule(reminder.schedule.clone());
+            }
+        }
+
+        reminder.touch();
+        self.repository.update_reminder(&reminder)?;
+        self.sync_reminder_job(&reminder).await?;
+        self.save_data().await?;
+        Ok(reminder)
+    }
+
+    /// 从假期汇总中移除提醒，提醒保持暂停
+    pub async fn dismiss_after_vacation(&self, reminder_id: &str) -> AppResult<AppSettings> {
+        self.take_vacation_item(reminder_id)?;
+        self.save_data().await?;
+        self.repository.get_settings()
+    }
+
+    fn take_vacation_item(&self, reminder_id: &str) -> AppResult<()> {
+        let mut settings = self.repository.get_settings()?;
+        let summary = settings
+            .vacation_summary
+            .as_mut()
+            .filter(|summary| summary.reminders.iter().any(|m| m.reminder_id == reminder_id))
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Reminder {} is not in the vacation summary",
+                    reminder_id
+                ))
+            })?;
+
+        summary.reminders.retain(|m| m.reminder_id != reminder_id);
+        if summary.reminders.is_empty() {
+            settings.vacation_summary = None;
+        }
+        self.repository.update_settings(&settings)
+    }
+
+    /// 校验请求引用时使用的分组、情境和标签
+    fn references(&self) -> AppResult<References> {
+        Ok(References {
+            groups: self.repository.get_groups()?,
+            contexts: self.repository.get_contexts()?,
+            tags: self.repository.get_tags()?,
+        })
+    }
+
+    fn validate_contexts(&self, context_ids: &[String]) -> AppResult<()> {
+        let contexts = self.repository.get_contexts()?;
+        match context_ids
+            .iter()
+            .find(|id| !contexts.iter().any(|c| &c.id == *id))
+        {
+            Some(id) => Err(AppError::NotFound(format!("Context with id {} not found", id))),
+            None => Ok(()),
+        }
+    }
+
     pub async fn restore_reminder_jobs(&self) -> AppResult<()> {
         let reminders = self.repository.get_reminders()?;
         let mut scheduler = self.scheduler.write().await;
@@ -197,10 +1597,206 @@ impl ReminderService {
         Ok(())
     }
 
-    async fn save_data(&self) -> AppResult<()> {
-        let groups = self.repository.get_groups()?;
+    /// 对比调度器与仓库，列出所有任务及不一致项
+    pub async fn get_scheduler_diagnostics(&self) -> AppResult<SchedulerDiagnostics> {
         let reminders = self.repository.get_reminders()?;
-        self.persistence.save_data(&groups, &reminders)?;
+        let jobs = self.scheduler.read().await.inspect_jobs()?;
+        let issues = Self::find_scheduler_issues(&reminders, &jobs);
+        Ok(SchedulerDiagnostics { jobs, issues })
+    }
+
+    /// 修复调度器与仓库之间的不一致
+    pub async fn reconcile_scheduler(&self) -> AppResult<ReconcileReport> {
+        let before = self.get_scheduler_diagnostics().await?;
+
+        let mut scheduler = self.scheduler.write().await;
+        for issue in &before.issues {
+            match issue {
+                SchedulerIssue::StaleJob { job_id, .. } => {
+                    let job_id = uuid::Uuid::parse_str(job_id)
+                        .map_err(|e| AppError::Internal(format!("Invalid job id: {}", e)))?;
+                    scheduler.remove_job(job_id)?;
+                }
+                SchedulerIssue::DuplicateJobs { reminder_id, job_ids } => {
+                    for job_id in job_ids {
+                        let job_id = uuid::Uuid::parse_str(job_id)
+                            .map_err(|e| AppError::Internal(format!("Invalid job id: {}", e)))?;
+                        scheduler.remove_job(job_id)?;
+                    }
+                    if let Some(reminder) = self.repository.find_reminder(reminder_id)? {
+                        if let Err(e) = scheduler.add_reminder_job(&reminder).await {
+                            eprintln!("Failed to add reminder job {}: {}", reminder_id, e);
+                        }
+                    }
+                }
+                SchedulerIssue::MissingJob { reminder_id } => {
+                    if let Some(reminder) = self.repository.find_reminder(reminder_id)? {
+                        if let Err(e) = scheduler.add_reminder_job(&reminder).await {
+                            eprintln!("Failed to add reminder job {}: {}", reminder_id, e);
+                        }
+                    }
+                }
+            }
+        }
+        drop(scheduler);
+
+        println!("Reconciled {} scheduler issues", before.issues.len());
+        Ok(ReconcileReport {
+            repaired: before.issues,
+            diagnostics: self.get_scheduler_diagnostics().await?,
+        })
+    }
+
+    fn find_scheduler_issues(
+        reminders: &[Reminder],
+        jobs: &[ScheduledJobInfo],
+    ) -> Vec<SchedulerIssue> {
+        let mut jobs_by_reminder: HashMap<&str, Vec<&ScheduledJobInfo>> = HashMap::new();
+        for job in jobs {
+            jobs_by_reminder
+                .entry(job.reminder_id.as_str())
+                .or_default()
+                .push(job);
+        }
+
+        let mut issues = Vec::new();
+        for (reminder_id, reminder_jobs) in &jobs_by_reminder {
+            let is_active = reminders
+                .iter()
+                .any(|r| r.id == *reminder_id && r.is_active());
+
+            if !is_active {
+                for job in reminder_jobs {
+                    issues.push(SchedulerIssue::StaleJob {
+                        reminder_id: reminder_id.to_string(),
+                        job_id: job.job_id.clone(),
+                    });
+                }
+            } else if reminder_jobs.len() > 1 {
+                issues.push(SchedulerIssue::DuplicateJobs {
+                    reminder_id: reminder_id.to_string(),
+                    job_ids: reminder_jobs.iter().map(|job| job.job_id.clone()).collect(),
+                });
+            }
+        }
+
+        // 没有有效调度计划的提醒本就无法注册任务
+        for reminder in reminders {
+            if reminder.is_active()
+                && schedule_for(reminder).is_ok()
+                && !jobs_by_reminder.contains_key(reminder.id.as_str())
+            {
+                issues.push(SchedulerIssue::MissingJob {
+                    reminder_id: reminder.id.clone(),
+                });
+            }
+        }
+
+        issues
+    }
+
+    async fn save_data(&self) -> AppResult<()> {
+        // 整体保存会带上调度线程的修改
+        self.repository.take_unsaved();
+        self.persistence.save_data(&AppData {
+            groups: self.repository.get_groups()?,
+            reminders: self.repository.get_reminders()?,
+            routines: self.repository.get_routines()?,
+            contexts: self.repository.get_contexts()?,
+            tags: self.repository.get_tags()?,
+            settings: self.repository.get_settings()?,
+            occurrence_log: self.repository.get_occurrence_log()?,
+        })?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::JobRunResult;
+    use crate::repository::InMemoryRepository;
+
+    #[test]
+    fn completing_a_cron_reminder_builds_a_streak() {
+        let schedule = Schedule::Cron { expression: "0 0 9 * * *".to_string() };
+        let reminder = Reminder::new(
+            "喝水".to_string(),
+            "#007AFF".to_string(),
+            "default".to_string(),
+            schedule,
+            None,
+        );
+        let repository = InMemoryRepository::new(AppData {
+            reminders: vec![reminder.clone()],
+            ..AppData::default()
+        });
+
+        let now = chrono::Utc::now().timestamp();
+        for days_ago in [2, 1] {
+            let fired = OccurrenceLogEntry::new(
+                &reminder.id,
+                now - days_ago * 86400,
+                JobRunResult::Notified,
+            );
+            repository.add_occurrence_log_entry(fired).unwrap();
+            let completed = ReminderService::record_completion(&repository, &reminder.id).unwrap();
+            assert_eq!(completed.status, ReminderStatus::Active);
+        }
+
+        let reminder = repository.find_reminder(&reminder.id).unwrap().unwrap();
+        assert!(schedule_for(&reminder).is_ok());
+        let report = ReminderService::stats_report(&repository, now).unwrap();
+        assert_eq!(report.reminders[0].stats.completed, 2);
+        assert_eq!(report.reminders[0].stats.current_streak, 2);
+    }
+
+    fn cron_reminder(title: &str, group_id: &str) -> Reminder {
+        Reminder::new(
+            title.to_string(),
+            "#007AFF".to_string(),
+            group_id.to_string(),
+            Schedule::Cron { expression: "0 0 9 * * *".to_string() },
+            None,
+        )
+    }
+
+    #[test]
+    fn group_stats_merge_member_outcomes_by_time() {
+        let group = ReminderGroup::new("健康".to_string(), "#007AFF".to_string());
+        let first = cron_reminder("喝水", &group.id);
+        let second = cron_reminder("拉伸", &group.id);
+        let mut deleted = cron_reminder("散步", &group.id);
+        deleted.status = ReminderStatus::Deleted;
+
+        let now = chrono::Utc::now().timestamp();
+        let answered = |reminder: &Reminder, days_ago: i64, response| {
+            let mut entry = OccurrenceLogEntry::new(
+                &reminder.id,
+                now - days_ago * 86400,
+                JobRunResult::Notified,
+            );
+            entry.respond(response, entry.scheduled_at + 60);
+            entry
+        };
+        let repository = InMemoryRepository::new(AppData {
+            groups: vec![group.clone()],
+            occurrence_log: vec![
+                answered(&first, 3, OccurrenceResponse::Completed),
+                answered(&second, 2, OccurrenceResponse::Missed),
+                answered(&deleted, 2, OccurrenceResponse::Completed),
+                answered(&first, 1, OccurrenceResponse::Completed),
+            ],
+            reminders: vec![first, second, deleted],
+            ..AppData::default()
+        });
+
+        let report = ReminderService::stats_report(&repository, now).unwrap();
+        assert_eq!(report.reminders.len(), 2);
+        let stats = &report.groups[0].stats;
+        assert_eq!(stats.tracked, 3);
+        assert_eq!(stats.completed, 2);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.best_streak, 1);
+    }
+}
@@ -1,7 +1,7 @@
 
 
 
-use chrono::{Local, Timelike};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Timelike};
 
 pub fn get_current_time()->(u32, u32) {
 
@@ -10,3 +10,29 @@ pub fn get_current_time()->(u32, u32) {
   let minutes = now.minute();
   (hour, minutes)
 }
+
+/// 解析本地时间字符串，支持 `YYYY-MM-DD HH:MM` 与 RFC 3339 两种格式
+pub fn parse_local_datetime(value: &str) -> Option<DateTime<Local>> {
+  if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+    return Some(datetime.with_timezone(&Local));
+  }
+  NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+    .ok()
+    .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+}
+
+/// 把时长格式化为中文描述，如 `2天3小时`、`45分钟`
+pub fn format_duration(seconds: i64) -> String {
+  let minutes = seconds.abs() / 60;
+  let (days, hours, minutes) = (minutes / 1440, minutes % 1440 / 60, minutes % 60);
+
+  if days > 0 {
+    if hours > 0 { format!("{}天{}小时", days, hours) } else { format!("{}天", days) }
+  } else if hours > 0 {
+    if minutes > 0 { format!("{}小时{}分钟", hours, minutes) } else { format!("{}小时", hours) }
+  } else if minutes > 0 {
+    format!("{}分钟", minutes)
+  } else {
+    "不到1分钟".to_string()
+  }
+}
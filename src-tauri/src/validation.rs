@@ -0,0 +1,460 @@
+use crate::error::{AppError, AppResult, FieldError};
+use crate::models::{
+    parse_hour_minute, Context, CreateGroupRequest, CreateReminderRequest, ParsedSchedule,
+    ReminderGroup, Schedule, ScheduleInput, Tag, TimeWindow, TimeWindowInput,
+    UpdateReminderRequest,
+};
+
+/// 提醒标题的最大长度（字符数）
+pub const MAX_TITLE_LENGTH: usize = 100;
+/// 分组名称的最大长度（字符数）
+pub const MAX_GROUP_NAME_LENGTH: usize = 50;
+/// 前端不使用分组时提交的分组 ID，视为未分组
+pub const DEFAULT_GROUP_ID: &str = "default";
+
+/// 校验请求中引用的分组、情境和标签时使用的现有数据
+pub struct References {
+    pub groups: Vec<ReminderGroup>,
+    pub contexts: Vec<Context>,
+    pub tags: Vec<Tag>,
+}
+
+/// 收集各字段的错误，全部检查完后一次返回
+#[derive(Default)]
+struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, message));
+    }
+
+    fn check(&mut self, field: impl Into<String>, result: AppResult<()>) {
+        if let Err(e) = result {
+            self.add(field, e.detail());
+        }
+    }
+
+    /// 记录共享校验的结果，字段错误加上 prefix 前缀，如 `schedule.expression`
+    fn check_nested(&mut self, prefix: &str, result: AppResult<()>) {
+        match result {
+            Ok(()) => {}
+            Err(AppError::InvalidFields(errors)) => {
+                for error in errors {
+                    self.add(format!("{}.{}", prefix, error.field), error.message);
+                }
+            }
+            Err(e) => self.add(prefix, e.detail()),
+        }
+    }
+
+    fn finish(self) -> AppResult<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(self.0))
+        }
+    }
+}
+
+/// 校验新建提醒的请求，返回解析后的调度计划和时间窗口
+pub fn validate_create_reminder(
+    request: &CreateReminderRequest,
+    references: &References,
+) -> AppResult<(Schedule, Option<Vec<TimeWindow>>)> {
+    let mut errors = FieldErrors::default();
+    errors.check("title", validate_name("Title", &request.title, MAX_TITLE_LENGTH));
+    errors.check("color", validate_color(&request.color));
+    if request.group_id != DEFAULT_GROUP_ID
+        && !references.groups.iter().any(|g| g.id == request.group_id)
+    {
+        errors.add("group_id", format!("Group with id {} not found", request.group_id));
+    }
+    let schedule = parse_schedule_input(&mut errors, &request.schedule);
+    let windows = request
+        .windows
+        .as_deref()
+        .map(|windows| parse_windows(&mut errors, windows));
+    if let Some(contexts) = &request.contexts {
+        check_references(&mut errors, "contexts", "Context", contexts, &references.contexts, |c| {
+            &c.id
+        });
+    }
+    if let Some(tags) = &request.tags {
+        check_references(&mut errors, "tags", "Tag", tags, &references.tags, |t| &t.id);
+    }
+    errors.finish()?;
+
+    let schedule = schedule
+        .ok_or_else(|| AppError::Internal("Schedule missing after validation".to_string()))?;
+    Ok((schedule, windows))
+}
+
+/// 只检查请求中设置了的字段
+pub fn validate_update_reminder(
+    request: &UpdateReminderRequest,
+    references: &References,
+) -> AppResult<ParsedSchedule> {
+    let mut errors = FieldErrors::default();
+    if let Some(title) = &request.title {
+        errors.check("title", validate_name("Title", title, MAX_TITLE_LENGTH));
+    }
+    if let Some(color) = &request.color {
+        errors.check("color", validate_color(color));
+    }
+    let schedule = request
+        .schedule
+        .as_ref()
+        .and_then(|schedule| parse_schedule_input(&mut errors, schedule));
+    let windows = request
+        .windows
+        .as_deref()
+        .map(|windows| parse_windows(&mut errors, windows));
+    if let Some(contexts) = &request.contexts {
+        check_references(&mut errors, "contexts", "Context", contexts, &references.contexts, |c| {
+            &c.id
+        });
+    }
+    if let Some(tags) = &request.tags {
+        check_references(&mut errors, "tags", "Tag", tags, &references.tags, |t| &t.id);
+    }
+    errors.finish()?;
+    Ok(ParsedSchedule { schedule, windows })
+}
+
+pub fn validate_create_group(
+    request: &CreateGroupRequest,
+    references: &References,
+) -> AppResult<()> {
+    let mut errors = FieldErrors::default();
+    errors.check("name", validate_name("Name", &request.name, MAX_GROUP_NAME_LENGTH));
+    errors.check("color", validate_color(&request.color));
+    if let Some(contexts) = &request.contexts {
+        check_references(&mut errors, "contexts", "Context", contexts, &references.contexts, |c| {
+            &c.id
+        });
+    }
+    errors.finish()
+}
+
+fn validate_name(label: &str, value: &str, max_length: usize) -> AppResult<()> {
+    if value.trim().is_empty() {
+        return Err(AppError::Validation(format!("{} must not be empty", label)));
+    }
+    if value.chars().count() > max_length {
+        return Err(AppError::Validation(format!(
+            "{} must be at most {} characters",
+            label, max_length
+        )));
+    }
+    Ok(())
+}
+
+/// 颜色为 `#RGB` 或 `#RRGGBB` 格式的十六进制值
+fn validate_color(color: &str) -> AppResult<()> {
+    let valid = color.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid color {}, expected #RGB or #RRGGBB",
+            color
+        )));
+    }
+    Ok(())
+}
+
+/// 每个不存在的 ID 单独报告，字段为 `contexts[1]` 这样的路径
+fn check_references<T>(
+    errors: &mut FieldErrors,
+    field: &str,
+    label: &str,
+    ids: &[String],
+    existing: &[T],
+    id_of: impl Fn(&T) -> &String,
+) {
+    for (index, id) in ids.iter().enumerate() {
+        if !existing.iter().any(|item| id_of(item) == id) {
+            let message = format!("{} with id {} not found", label, id);
+            errors.add(format!("{}[{}]", field, index), message);
+        }
+    }
+}
+
+/// 解析调度计划，取值由 Schedule::validate 检查，有错误时记录到对应字段并返回 None
+fn parse_schedule_input(errors: &mut FieldErrors, input: &ScheduleInput) -> Option<Schedule> {
+    let before = errors.0.len();
+    let schedule = match input.kind.as_str() {
+        "cron" => Schedule::Cron {
+            expression: input.expression.as_deref().unwrap_or_default().trim().to_string(),
+        },
+        "once" => Schedule::Once {
+            at: required(errors, "schedule.at", input.at),
+        },
+        "interval" => Schedule::Interval {
+            minutes: required_count(errors, "schedule.minutes", input.minutes),
+        },
+        "after_completion" => Schedule::AfterCompletion {
+            interval_days: required_count(errors, "schedule.interval_days", input.interval_days),
+        },
+        "deadline" => {
+            let due_at = required(errors, "schedule.due_at", input.due_at);
+            let alert_minutes_before = input.alert_minutes_before.as_ref().map(|alerts| {
+                alerts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, minutes)| {
+                        let field = format!("schedule.alert_minutes_before[{}]", index);
+                        required_count(errors, &field, Some(*minutes))
+                    })
+                    .collect()
+            });
+            let overdue_repeat_minutes = input.overdue_repeat_minutes.map(|minutes| {
+                required_count(errors, "schedule.overdue_repeat_minutes", Some(minutes))
+            });
+            Schedule::deadline(due_at, alert_minutes_before, overdue_repeat_minutes)
+        }
+        "" => {
+            errors.add("schedule.type", "Schedule type is required");
+            return None;
+        }
+        other => {
+            errors.add(
+                "schedule.type",
+                format!(
+                    "Unknown schedule type {}, expected cron, once, interval, after_completion \
+                     or deadline",
+                    other
+                ),
+            );
+            return None;
+        }
+    };
+    if errors.0.len() != before {
+        return None;
+    }
+    errors.check_nested("schedule", schedule.validate());
+    (errors.0.len() == before).then_some(schedule)
+}
+
+fn required(errors: &mut FieldErrors, field: &str, value: Option<i64>) -> i64 {
+    value.unwrap_or_else(|| {
+        errors.add(field, "This field is required");
+        0
+    })
+}
+
+fn required_count(errors: &mut FieldErrors, field: &str, value: Option<i64>) -> u32 {
+    match value.map(u32::try_from) {
+        Some(Ok(count)) => count,
+        Some(Err(_)) => {
+            errors.add(field, "Must be a whole number");
+            0
+        }
+        None => {
+            errors.add(field, "This field is required");
+            0
+        }
+    }
+}
+
+/// 逐个解析时间窗口，取值由 TimeWindow::validate 检查，
+/// 错误对应到 `windows[0].start` 这样的字段
+fn parse_windows(errors: &mut FieldErrors, windows: &[TimeWindowInput]) -> Vec<TimeWindow> {
+    let mut parsed = Vec::new();
+    for (index, window) in windows.iter().enumerate() {
+        let prefix = format!("windows[{}]", index);
+        match (parse_hour_minute(&window.start), parse_hour_minute(&window.end)) {
+            (Ok(start), Ok(end)) => {
+                let window = TimeWindow {
+                    weekdays: window.weekdays.clone(),
+                    start,
+                    end,
+                };
+                match window.validate() {
+                    Ok(()) => parsed.push(window),
+                    result => errors.check_nested(&prefix, result),
+                }
+            }
+            (start, end) => {
+                let weekdays = TimeWindow::validate_weekdays(&window.weekdays);
+                errors.check(format!("{}.weekdays", prefix), weekdays);
+                errors.check(format!("{}.start", prefix), start.map(|_| ()));
+                errors.check(format!("{}.end", prefix), end.map(|_| ()));
+            }
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn references() -> References {
+        let mut context = Context::new("工作".to_string());
+        context.id = "ctx".to_string();
+        let mut tag = Tag::new("健康".to_string());
+        tag.id = "tag".to_string();
+        References {
+            groups: Vec::new(),
+            contexts: vec![context],
+            tags: vec![tag],
+        }
+    }
+
+    /// 按前端提交的 JSON 构造请求，未给出的字段使用有效值
+    fn create_request(fields: serde_json::Value) -> CreateReminderRequest {
+        let mut value = json!({
+            "title": "喝水",
+            "color": "#007AFF",
+            "group_id": DEFAULT_GROUP_ID,
+            "schedule": { "type": "cron", "expression": "0 0 9 * * *" },
+            "description": null,
+            "checklist": null,
+            "windows": null,
+            "max_per_day": null,
+            "contexts": null,
+            "priority": null,
+            "tags": null,
+        });
+        if let (Some(value), Some(fields)) = (value.as_object_mut(), fields.as_object()) {
+            value.extend(fields.clone());
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn invalid_fields(result: AppResult<impl std::fmt::Debug>) -> Vec<String> {
+        match result {
+            Err(AppError::InvalidFields(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected field errors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_request_is_parsed() {
+        let request = create_request(json!({
+            "windows": [{ "weekdays": [1, 5], "start": "08:00", "end": "18:30" }],
+            "contexts": ["ctx"],
+            "tags": ["tag"],
+        }));
+
+        let (schedule, windows) = validate_create_reminder(&request, &references()).unwrap();
+
+        assert_eq!(schedule, Schedule::Cron { expression: "0 0 9 * * *".to_string() });
+        let windows = windows.unwrap();
+        assert_eq!(windows[0].weekdays, vec![1, 5]);
+        assert_eq!(windows[0].end.format("%H:%M").to_string(), "18:30");
+    }
+
+    #[test]
+    fn each_bad_field_is_reported() {
+        let request = create_request(json!({
+            "title": "  ",
+            "color": "blue",
+            "group_id": "missing",
+            "schedule": { "type": "cron", "expression": "not a schedule at all" },
+            "windows": [
+                { "weekdays": [8], "start": "25:00", "end": "9am" },
+                { "start": "18:00", "end": "08:00" },
+            ],
+            "contexts": ["ctx", "gone"],
+            "tags": ["nope"],
+        }));
+
+        let fields = invalid_fields(validate_create_reminder(&request, &references()));
+
+        assert_eq!(
+            fields,
+            vec![
+                "title",
+                "color",
+                "group_id",
+                "schedule.expression",
+                "windows[0].weekdays",
+                "windows[0].start",
+                "windows[0].end",
+                "windows[1].end",
+                "contexts[1]",
+                "tags[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_schedule_type_is_a_field_error() {
+        let request = create_request(json!({ "schedule": { "type": "weekly" } }));
+
+        let fields = invalid_fields(validate_create_reminder(&request, &references()));
+
+        assert_eq!(fields, vec!["schedule.type"]);
+    }
+
+    #[test]
+    fn schedule_fields_are_checked_per_type() {
+        let cases = [
+            (json!({ "type": "once" }), "schedule.at"),
+            (json!({ "type": "interval", "minutes": 0 }), "schedule.minutes"),
+            (json!({ "type": "after_completion", "interval_days": -1 }), "schedule.interval_days"),
+            (
+                json!({
+                    "type": "deadline",
+                    "due_at": 1_900_000_000,
+                    "alert_minutes_before": [60, -5],
+                }),
+                "schedule.alert_minutes_before[1]",
+            ),
+        ];
+        for (schedule, field) in cases {
+            let request = create_request(json!({ "schedule": schedule }));
+
+            let fields = invalid_fields(validate_create_reminder(&request, &references()));
+
+            assert_eq!(fields, vec![field]);
+        }
+    }
+
+    #[test]
+    fn deadline_defaults_are_filled() {
+        let request = create_request(json!({
+            "schedule": { "type": "deadline", "due_at": 1_900_000_000 },
+        }));
+
+        let (schedule, _) = validate_create_reminder(&request, &references()).unwrap();
+
+        assert_eq!(schedule, Schedule::deadline(1_900_000_000, None, None));
+    }
+
+    #[test]
+    fn update_checks_only_given_fields() {
+        let request: UpdateReminderRequest = serde_json::from_value(json!({
+            "title": null,
+            "color": null,
+            "schedule": null,
+            "description": null,
+            "windows": [],
+            "max_per_day": null,
+            "contexts": ["gone"],
+            "priority": null,
+            "tags": null,
+        }))
+        .unwrap();
+
+        let fields = invalid_fields(validate_update_reminder(&request, &references()));
+
+        assert_eq!(fields, vec!["contexts[0]"]);
+    }
+
+    #[test]
+    fn group_name_and_color_are_checked() {
+        let request = CreateGroupRequest {
+            name: "x".repeat(MAX_GROUP_NAME_LENGTH + 1),
+            color: "#12345".to_string(),
+            contexts: Some(vec!["ctx".to_string()]),
+        };
+
+        let fields = invalid_fields(validate_create_group(&request, &references()));
+
+        assert_eq!(fields, vec!["name", "color"]);
+    }
+}
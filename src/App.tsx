@@ -46,8 +46,8 @@ function App() {
     try {
       await api.cancelReminder(reminderId);
       setReminders((prev) =>
-        prev.map((r) =>
-          r.id === reminderId ? { ...r, is_cancelled: true } : r
+        prev.map((r): Reminder =>
+          r.id === reminderId ? { ...r, status: 'cancelled' } : r
         )
       );
     } catch (err) {
@@ -14,7 +14,7 @@ export const ReminderList: React.FC<ReminderListProps> = ({
   onCancelReminder,
 }) => {
   const activeReminders = reminders.filter(
-    (r) => !r.is_cancelled && !r.is_deleted
+    (r) => r.status !== 'cancelled' && r.status !== 'deleted
   );
   const { groupedReminders } = useTimeGrouping(activeReminders);
 
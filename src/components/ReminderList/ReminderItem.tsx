@@ -1,7 +1,13 @@
 import React from 'react';
-import { Reminder } from '../../types';
+import { Reminder, ReminderStatus } from '../../types';
 import { useFormatters } from '../../hooks/useFormatters';
 
+const STATUS_LABELS: Partial<Record<ReminderStatus, string>> = {
+  paused: '已暂停',
+  cancelled: '已取消',
+  completed: '已完成',
+};
+
 interface ReminderItemProps {
   reminder: Reminder;
   onCancel: (reminderId: string) => void;
@@ -23,15 +29,14 @@ export const ReminderItem: React.FC<ReminderItemProps> = ({
         <div className="reminder-details">
           <div className="reminder-title">{reminder.title}</div>
           <div className="reminder-description">
-            {formatDateTime(reminder.start_at)}
-            {reminder.cron_expression && ` • 重复: ${reminder.cron_expression}`}
+            {formatDateTime(reminder.created_at)}
+            {reminder.schedule.type === 'cron' && ` • 重复: ${reminder.schedule.expression}`}
             {reminder.description && ` • ${reminder.description}`}
-            {reminder.is_cancelled && ' • 已取消'}
-            {reminder.is_paused && ' • 已暂停'}
+            {STATUS_LABELS[reminder.status] && ` • ${STATUS_LABELS[reminder.status]}`}
           </div>
         </div>
         <div className="reminder-actions">
-          {!reminder.is_cancelled && (
+          {(reminder.status === 'active' || reminder.status === 'paused') && (
             <button
               onClick={() => onCancel(reminder.id)}
               className="reminder-action-button"
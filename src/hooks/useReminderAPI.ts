@@ -1,5 +1,30 @@
 import { invoke } from '@tauri-apps/api/core';
-import { ReminderGroup, Reminder, CreateGroupData, CreateReminderData } from '../types';
+import {
+  ReminderGroup,
+  Reminder,
+  CreateGroupData,
+  CreateReminderData,
+  Schedule,
+} from '../types';
+
+// 未填写定时规则时为一次性提醒：在开始时间点触发（今天已过则为明天），未选择时一分钟后触发
+const scheduleFor = (data: CreateReminderData): Schedule => {
+  const expression = data.cron_expression?.trim();
+  if (expression) {
+    return { type: 'cron', expression };
+  }
+  const now = new Date();
+  if (!data.startTime) {
+    return { type: 'once', at: Math.floor(now.getTime() / 1000) + 60 };
+  }
+  const [hours, minutes] = data.startTime.split(':').map(Number);
+  const at = new Date(now);
+  at.setHours(hours, minutes, 0, 0);
+  if (at <= now) {
+    at.setDate(at.getDate() + 1);
+  }
+  return { type: 'once', at: Math.floor(at.getTime() / 1000) };
+};
 
 export const useReminderAPI = () => {
   const getGroups = async (): Promise<ReminderGroup[]> => {
@@ -26,10 +51,12 @@ export const useReminderAPI = () => {
       title: data.title,
       color: data.color,
       groupId: data.group_id,
-      cronExpression: data.cron_expression,
+      schedule: scheduleFor(data),
       description: data.description,
-      startAt: data.startTime,
-      endAt: data.endTime,
+      windows:
+        data.startTime || data.endTime
+          ? [{ weekdays: [], start: data.startTime ?? '00:00', end: data.endTime ?? '23:59' }]
+          : undefined,
     });
   };
 
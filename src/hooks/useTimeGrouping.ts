@@ -11,7 +11,7 @@ export const useTimeGrouping = (reminders: Reminder[]) => {
 
     // 按日期和时间段分组
     reminders.forEach(reminder => {
-      const reminderDate = new Date(reminder.start_at * 1000);
+      const reminderDate = new Date(reminder.created_at * 1000);
       const today = new Date();
       const tomorrow = new Date(today);
       tomorrow.setDate(today.getDate() + 1);
@@ -59,7 +59,7 @@ export const useTimeGrouping = (reminders: Reminder[]) => {
 
     // 排序每个组内的提醒
     Object.keys(groupedReminders).forEach(key => {
-      groupedReminders[key].sort((a, b) => a.start_at - b.start_at);
+      groupedReminders[key].sort((a, b) => a.created_at - b.created_at);
     });
 
     return { groupedReminders };
@@ -3,20 +3,46 @@ export interface Reminder {
   title: string;
   color: string;
   group_id: string; // 保留，但使用默认值
-  cron_expression?: string;
-  start_at: number;
+  schedule: Schedule;
+  windows: TimeWindow[];
   last_triggered?: number;
-  is_cancelled: boolean;
-  is_deleted: boolean;
-  is_paused: boolean;
+  status: ReminderStatus;
   description?: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export type ReminderStatus =
+  | 'active'
+  | 'paused'
+  | 'cancelled'
+  | 'completed'
+  | 'deleted';
+
+export type Schedule =
+  | { type: 'cron'; expression: string }
+  | { type: 'once'; at: number }
+  | { type: 'interval'; minutes: number }
+  | { type: 'after_completion'; interval_days: number }
+  | {
+      type: 'deadline';
+      due_at: number;
+      alert_minutes_before?: number[];
+      overdue_repeat_minutes?: number;
+    }
+  | { type: 'unscheduled' }; // 旧数据中没有有效计划的提醒，保持暂停
+
+export interface TimeWindow {
+  weekdays: number[]; // 1-7 表示周一至周日，为空表示每天
+  start: string; // HH:MM
+  end: string; // HH:MM
 }
 
 export interface CreateReminderData {
   title: string;
   color: string;
   group_id: string; // 保留，但使用默认值
-  cron_expression?: string;
+  cron_expression?: string; // 留空为一次性提醒
   description?: string;
   startTime?: string; // 用于选择开始时间
   endTime?: string; // 用于选择结束时间
@@ -27,10 +53,21 @@ export interface ReminderGroup {
   id: string;
   name: string;
   color: string;
-  start_at: number;
+  created_at: number;
+  updated_at: number;
 }
 
 export interface CreateGroupData {
   name: string;
   color: string;
 }
+
+// 创建、更新提醒和创建分组时字段校验失败返回的错误
+export interface FieldError {
+  field: string; // 字段路径，如 windows[0]
+  message: string;
+}
+
+export interface FieldErrors {
+  fields: FieldError[];
+}